@@ -0,0 +1,182 @@
+use crate::{changeset_dir, stringify, Changeset, ReadError, Release};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+const LEGACY_CHANGES_FILE: &str = "changes.json";
+const LEGACY_SUMMARY_FILE: &str = "changes.md";
+
+#[derive(Debug, Deserialize)]
+struct LegacyChanges {
+  releases: Vec<Release>,
+  #[serde(default)]
+  summary: Option<String>,
+}
+
+fn legacy_dirs(root: &Path) -> Result<Vec<PathBuf>, ReadError> {
+  let dir = changeset_dir(root);
+  let mut dirs = std::fs::read_dir(&dir)
+    .map_err(|error| ReadError::Io(dir.clone(), error))?
+    .flatten()
+    .map(|entry| entry.path())
+    .filter(|path| path.join(LEGACY_CHANGES_FILE).is_file())
+    .collect::<Vec<_>>();
+  dirs.sort();
+  Ok(dirs)
+}
+
+fn read_legacy_changeset(dir: &Path) -> Result<Changeset, ReadError> {
+  let path = dir.join(LEGACY_CHANGES_FILE);
+  let source =
+    std::fs::read_to_string(&path).map_err(|error| ReadError::Io(path.clone(), error))?;
+  let changes: LegacyChanges =
+    serde_json::from_str(&source).map_err(|error| ReadError::Json(path, error))?;
+
+  let path = dir.join(LEGACY_SUMMARY_FILE);
+  let summary = match std::fs::read_to_string(&path) {
+    Ok(summary) => summary,
+    Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+      changes.summary.unwrap_or_default()
+    }
+    Err(error) => return Err(ReadError::Io(path, error)),
+  };
+
+  Ok(Changeset {
+    id: dir
+      .file_name()
+      .map(|name| name.to_string_lossy().to_string())
+      .unwrap_or_default(),
+    summary: summary.trim().to_string(),
+    releases: changes.releases,
+  })
+}
+
+/// Reads v1 changesets, which are directories holding `changes.json` and `changes.md`.
+pub fn read_legacy_changesets(root: &Path) -> Result<Vec<Changeset>, ReadError> {
+  legacy_dirs(root)?
+    .iter()
+    .map(|dir| read_legacy_changeset(dir))
+    .collect()
+}
+
+/// Rewrites every v1 changeset as a markdown changeset, returning the written files. Nothing is
+/// written when a markdown changeset already has the id of a v1 one.
+pub fn migrate_legacy_changesets(root: &Path) -> Result<Vec<PathBuf>, ReadError> {
+  let mut changesets = vec![];
+  for dir in legacy_dirs(root)? {
+    let changeset = read_legacy_changeset(&dir)?;
+    let path = changeset_dir(root).join(format!("{}.md", changeset.id));
+    if path.exists() {
+      return Err(ReadError::DuplicateId(changeset.id));
+    }
+    changesets.push((dir, changeset, path));
+  }
+
+  let mut written = vec![];
+  for (dir, changeset, path) in changesets {
+    std::fs::write(&path, stringify(&changeset.summary, &changeset.releases))
+      .map_err(|error| ReadError::Io(path.clone(), error))?;
+    std::fs::remove_dir_all(&dir).map_err(|error| ReadError::Io(dir, error))?;
+    written.push(path);
+  }
+  Ok(written)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{read_changesets, remove_changeset, VersionType};
+
+  fn write_legacy(root: &Path, id: &str) {
+    let dir = changeset_dir(root).join(id);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+      dir.join(LEGACY_CHANGES_FILE),
+      r#"{"releases":[{"name":"@scope/a","type":"minor"}],"dependents":[{"name":"b","type":"patch","dependencies":["@scope/a"]}]}"#,
+    )
+    .unwrap();
+    std::fs::write(dir.join(LEGACY_SUMMARY_FILE), "Add a feature\n").unwrap();
+  }
+
+  #[test]
+  fn reads_legacy_changesets() {
+    let dir = tempfile::tempdir().unwrap();
+    write_legacy(dir.path(), "brave-cats-jump");
+    std::fs::write(
+      changeset_dir(dir.path()).join("a-new.md"),
+      "---\nb: patch\n---\n\nnew",
+    )
+    .unwrap();
+
+    let changesets = read_changesets(dir.path()).unwrap();
+    assert_eq!(
+      changesets[1],
+      Changeset {
+        id: String::from("brave-cats-jump"),
+        summary: String::from("Add a feature"),
+        releases: vec![Release {
+          name: String::from("@scope/a"),
+          r#type: VersionType::Minor,
+        }],
+      }
+    );
+
+    remove_changeset(dir.path(), &changesets[1]).unwrap();
+    assert!(!changeset_dir(dir.path()).join("brave-cats-jump").exists());
+  }
+
+  #[test]
+  fn migrates_legacy_changesets() {
+    let dir = tempfile::tempdir().unwrap();
+    write_legacy(dir.path(), "brave-cats-jump");
+
+    let written = migrate_legacy_changesets(dir.path()).unwrap();
+    assert_eq!(
+      written,
+      vec![changeset_dir(dir.path()).join("brave-cats-jump.md")]
+    );
+    assert_eq!(
+      std::fs::read_to_string(&written[0]).unwrap(),
+      "---\n\"@scope/a\": minor\n---\n\nAdd a feature\n"
+    );
+    assert!(read_legacy_changesets(dir.path()).unwrap().is_empty());
+    assert_eq!(read_changesets(dir.path()).unwrap().len(), 1);
+  }
+
+  #[test]
+  fn reject_duplicate_ids() {
+    let dir = tempfile::tempdir().unwrap();
+    write_legacy(dir.path(), "brave-cats-jump");
+    write_legacy(dir.path(), "calm-dogs-run");
+    let existing = changeset_dir(dir.path()).join("calm-dogs-run.md");
+    std::fs::write(
+      &existing,
+      "---
+b: patch
+---
+
+new",
+    )
+    .unwrap();
+
+    assert!(matches!(
+      read_changesets(dir.path()),
+      Err(ReadError::DuplicateId(id)) if id == "calm-dogs-run"
+    ));
+    assert!(matches!(
+      migrate_legacy_changesets(dir.path()),
+      Err(ReadError::DuplicateId(id)) if id == "calm-dogs-run"
+    ));
+    assert_eq!(
+      std::fs::read_to_string(&existing).unwrap(),
+      "---
+b: patch
+---
+
+new"
+    );
+    assert!(!changeset_dir(dir.path())
+      .join("brave-cats-jump.md")
+      .exists());
+    assert_eq!(read_legacy_changesets(dir.path()).unwrap().len(), 2);
+  }
+}
@@ -0,0 +1,44 @@
+mod legacy;
+mod parse;
+mod pending;
+mod read;
+
+pub use legacy::*;
+pub use parse::*;
+pub use pending::*;
+pub use read::*;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum VersionType {
+  None,
+  Patch,
+  Minor,
+  Major,
+}
+
+impl VersionType {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::None => "none",
+      Self::Patch => "patch",
+      Self::Minor => "minor",
+      Self::Major => "major",
+    }
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Release {
+  pub name: String,
+  pub r#type: VersionType,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Changeset {
+  pub id: String,
+  pub summary: String,
+  pub releases: Vec<Release>,
+}
@@ -0,0 +1,103 @@
+use crate::{Release, VersionType};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ParseError {
+  #[error("could not parse changeset - missing or invalid frontmatter.\nChangesets must start with frontmatter delimited by \"---\".")]
+  MissingFrontmatter,
+  #[error("could not parse changeset - invalid frontmatter: {0}")]
+  InvalidFrontmatter(serde_yaml::Error),
+}
+
+pub fn parse(contents: &str) -> Result<(String, Vec<Release>), ParseError> {
+  let contents = contents.trim_start();
+  let rest = contents
+    .strip_prefix("---")
+    .ok_or(ParseError::MissingFrontmatter)?;
+  let end = rest.find("\n---").ok_or(ParseError::MissingFrontmatter)?;
+  let (frontmatter, summary) = (&rest[..end], &rest[end + "\n---".len()..]);
+
+  let releases = serde_yaml::from_str::<Option<serde_yaml::Mapping>>(frontmatter)
+    .map_err(ParseError::InvalidFrontmatter)?
+    .unwrap_or_default()
+    .into_iter()
+    .map(|(name, r#type)| {
+      Ok(Release {
+        name: serde_yaml::from_value(name).map_err(ParseError::InvalidFrontmatter)?,
+        r#type: serde_yaml::from_value::<VersionType>(r#type)
+          .map_err(ParseError::InvalidFrontmatter)?,
+      })
+    })
+    .collect::<Result<Vec<_>, ParseError>>()?;
+
+  Ok((summary.trim().to_string(), releases))
+}
+
+pub fn stringify(summary: &str, releases: &[Release]) -> String {
+  let frontmatter: String = releases
+    .iter()
+    .map(|release| {
+      format!(
+        "{}: {}\n",
+        serde_json::Value::from(release.name.as_str()),
+        release.r#type.as_str()
+      )
+    })
+    .collect();
+  format!("---\n{frontmatter}---\n\n{}\n", summary.trim())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn parse_changeset() {
+    let (summary, releases) = parse(
+      "---\n\"pkg-a\": minor\n'@scope/b': patch\nacme-client: major\n---\n\nAdd a feature\n\nWith details\n",
+    )
+    .unwrap();
+    assert_eq!(summary, "Add a feature\n\nWith details");
+    assert_eq!(
+      releases,
+      vec![
+        Release {
+          name: String::from("pkg-a"),
+          r#type: VersionType::Minor
+        },
+        Release {
+          name: String::from("@scope/b"),
+          r#type: VersionType::Patch
+        },
+        Release {
+          name: String::from("acme-client"),
+          r#type: VersionType::Major
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn stringify_round_trips() {
+    let source = "---\n\"pkg-a\": minor\n\"@scope/b\": patch\n---\n\nAdd a feature\n";
+    let (summary, releases) = parse(source).unwrap();
+    assert_eq!(stringify(&summary, &releases), source);
+  }
+
+  #[test]
+  fn parse_empty_changeset() {
+    assert_eq!(parse("---\n---\n").unwrap(), (String::new(), vec![]));
+  }
+
+  #[test]
+  fn parse_invalid_changeset() {
+    assert!(matches!(
+      parse("no frontmatter"),
+      Err(ParseError::MissingFrontmatter)
+    ));
+    assert!(matches!(
+      parse("---\na: huge\n---\n"),
+      Err(ParseError::InvalidFrontmatter(_))
+    ));
+  }
+}
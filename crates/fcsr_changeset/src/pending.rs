@@ -0,0 +1,52 @@
+use crate::{changeset_dir, ReadError};
+use std::{collections::BTreeMap, path::Path};
+
+const PENDING_VERSIONS_FILE: &str = "pending-versions.json";
+
+pub fn read_pending_versions(root: &Path) -> Result<BTreeMap<String, String>, ReadError> {
+  let path = changeset_dir(root).join(PENDING_VERSIONS_FILE);
+  match std::fs::read_to_string(&path) {
+    Ok(source) => serde_json::from_str(&source).map_err(|error| ReadError::Json(path, error)),
+    Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+    Err(error) => Err(ReadError::Io(path, error)),
+  }
+}
+
+pub fn write_pending_versions(
+  root: &Path,
+  versions: &BTreeMap<String, String>,
+) -> Result<(), ReadError> {
+  let path = changeset_dir(root).join(PENDING_VERSIONS_FILE);
+  if versions.is_empty() {
+    return match std::fs::remove_file(&path) {
+      Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(ReadError::Io(path, error)),
+      _ => Ok(()),
+    };
+  }
+
+  let mut contents =
+    serde_json::to_string_pretty(versions).map_err(|error| ReadError::Json(path.clone(), error))?;
+  contents.push('\n');
+  std::fs::write(&path, contents).map_err(|error| ReadError::Io(path, error))
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn round_trip_pending_versions() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(changeset_dir(dir.path())).unwrap();
+    assert!(read_pending_versions(dir.path()).unwrap().is_empty());
+
+    let versions = BTreeMap::from([(String::from("api-chart"), String::from("1.3.0"))]);
+    write_pending_versions(dir.path(), &versions).unwrap();
+    assert_eq!(read_pending_versions(dir.path()).unwrap(), versions);
+
+    write_pending_versions(dir.path(), &BTreeMap::new()).unwrap();
+    assert!(!changeset_dir(dir.path())
+      .join(PENDING_VERSIONS_FILE)
+      .exists());
+  }
+}
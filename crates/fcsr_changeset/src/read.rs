@@ -0,0 +1,105 @@
+use crate::{parse, read_legacy_changesets, Changeset, ParseError};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ReadError {
+  #[error("There is no .changeset directory in this project")]
+  NoChangesetDir,
+  #[error("Failed to read {0}: {1}")]
+  Io(PathBuf, std::io::Error),
+  #[error("{0}: {1}")]
+  Parse(PathBuf, ParseError),
+  #[error("Failed to parse {0}: {1}")]
+  Json(PathBuf, serde_json::Error),
+  #[error("Changeset {0} is both a {0}.md file and a legacy {0} directory, remove one of them")]
+  DuplicateId(String),
+}
+
+pub fn changeset_dir(root: &Path) -> PathBuf {
+  root.join(".changeset")
+}
+
+pub fn read_changesets(root: &Path) -> Result<Vec<Changeset>, ReadError> {
+  let dir = changeset_dir(root);
+  if !dir.is_dir() {
+    return Err(ReadError::NoChangesetDir);
+  }
+
+  let mut paths = std::fs::read_dir(&dir)
+    .map_err(|error| ReadError::Io(dir.clone(), error))?
+    .flatten()
+    .map(|entry| entry.path())
+    .filter(|path| {
+      path.is_file()
+        && path.extension().is_some_and(|extension| extension == "md")
+        && path.file_name().is_some_and(|name| name != "README.md")
+    })
+    .collect::<Vec<_>>();
+  paths.sort();
+
+  let mut changesets = vec![];
+  for path in paths {
+    let contents =
+      std::fs::read_to_string(&path).map_err(|error| ReadError::Io(path.clone(), error))?;
+    let (summary, releases) =
+      parse(&contents).map_err(|error| ReadError::Parse(path.clone(), error))?;
+    changesets.push(Changeset {
+      id: path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default(),
+      summary,
+      releases,
+    });
+  }
+  changesets.extend(read_legacy_changesets(root)?);
+  changesets.sort_by(|a, b| a.id.cmp(&b.id));
+  if let Some(pair) = changesets.windows(2).find(|pair| pair[0].id == pair[1].id) {
+    return Err(ReadError::DuplicateId(pair[0].id.clone()));
+  }
+  Ok(changesets)
+}
+
+pub fn remove_changeset(root: &Path, changeset: &Changeset) -> Result<(), ReadError> {
+  let legacy = changeset_dir(root).join(&changeset.id);
+  if legacy.is_dir() {
+    return std::fs::remove_dir_all(&legacy).map_err(|error| ReadError::Io(legacy, error));
+  }
+  let path = changeset_dir(root).join(format!("{}.md", changeset.id));
+  std::fs::remove_file(&path).map_err(|error| ReadError::Io(path, error))
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn read_changeset_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    let changeset_dir = changeset_dir(dir.path());
+    std::fs::create_dir_all(&changeset_dir).unwrap();
+    std::fs::write(changeset_dir.join("README.md"), "# Changesets").unwrap();
+    std::fs::write(changeset_dir.join("config.json"), "{}").unwrap();
+    std::fs::write(
+      changeset_dir.join("b-second.md"),
+      "---\na: patch\n---\n\nsecond",
+    )
+    .unwrap();
+    std::fs::write(
+      changeset_dir.join("a-first.md"),
+      "---\na: minor\n---\n\nfirst",
+    )
+    .unwrap();
+
+    let changesets = read_changesets(dir.path()).unwrap();
+    let ids: Vec<_> = changesets
+      .iter()
+      .map(|changeset| changeset.id.as_str())
+      .collect();
+    assert_eq!(ids, vec!["a-first", "b-second"]);
+
+    remove_changeset(dir.path(), &changesets[0]).unwrap();
+    assert_eq!(read_changesets(dir.path()).unwrap().len(), 1);
+  }
+}
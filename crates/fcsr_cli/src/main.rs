@@ -1,6 +1,20 @@
-use clap::Parser;
-use fcsr_core::{add::run_add, init::run_init, version::run_version};
-use fcsr_metadata::{Add, Init, Version};
+mod report;
+
+use clap::{Parser, Subcommand};
+use fcsr_core::{
+  add::run_add,
+  config::{run_config, ConfigCommandError},
+  fix_ranges::{run_fix_ranges, FixRangesError},
+  graph::{run_graph, GraphError},
+  init::run_init,
+  lint::{run_lint, LintError},
+  migrate::run_migrate,
+  tag::{run_tag, TagError},
+  version::{run_version, VersionError},
+};
+use fcsr_diagnostic::Diagnostics;
+use fcsr_metadata::{Add, Config, FixRanges, Graph, Init, Lint, Migrate, Tag, Version};
+use report::{command_failed, Reporter};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -9,23 +23,47 @@ use std::path::PathBuf;
   version = env!("CARGO_PKG_VERSION"),
   bin_name = "fcsr"
 )]
+pub struct Cli {
+  #[arg(long, global = true, value_enum, default_value_t = Reporter::Terminal)]
+  reporter: Reporter,
+  #[command(subcommand)]
+  command: Command,
+}
+
+#[derive(Subcommand)]
 pub enum Command {
   Init(Init),
   Version(Version),
   Add(Add),
+  Tag(Tag),
+  Config(Config),
+  Migrate(Migrate),
+  /// Print the internal dependency graph as DOT, Mermaid or JSON
+  Graph(Graph),
+  /// Rewrite internal dependency ranges that no longer include the current version
+  FixRanges(FixRanges),
+  /// Check the workspace against the `lint` policies of the config
+  Lint(Lint),
 }
 
 fn main() {
-  match Command::parse() {
+  let Cli { reporter, command } = Cli::parse();
+  let diagnostics = match command {
     Command::Add(add) => add.exec(),
     Command::Init(init) => init.exec(),
     Command::Version(version) => version.exec(),
-  }
+    Command::Tag(tag) => tag.exec(),
+    Command::Config(config) => config.exec(),
+    Command::Migrate(migrate) => migrate.exec(),
+    Command::Graph(graph) => graph.exec(),
+    Command::FixRanges(fix_ranges) => fix_ranges.exec(),
+    Command::Lint(lint) => lint.exec(),
+  };
+  reporter.report(&diagnostics);
 }
 
 trait Exec {
-  type Res;
-  fn exec(self);
+  fn exec(self) -> Diagnostics;
 
   fn get_pwd() -> PathBuf {
     std::env::current_dir().expect("Failed to get pwd")
@@ -33,28 +71,103 @@ trait Exec {
 }
 
 impl Exec for Add {
-  type Res = ();
-  fn exec(self) {
+  fn exec(self) -> Diagnostics {
+    let mut diagnostics = Diagnostics::default();
     if let Err(error) = run_add(self, Self::get_pwd()) {
-      println!("{error:?}");
+      diagnostics.push(command_failed(&error));
     }
+    diagnostics
   }
 }
 
 impl Exec for Init {
-  type Res = ();
-  fn exec(self) {
-    if let Err(error) = run_init(self, Self::get_pwd()) {
-      println!("{error:?}");
+  fn exec(self) -> Diagnostics {
+    let mut diagnostics = Diagnostics::default();
+    if let Err(error) = run_init(self, Self::get_pwd(), &mut diagnostics) {
+      diagnostics.push(command_failed(&error));
     }
+    diagnostics
   }
 }
 
 impl Exec for Version {
-  type Res = ();
-  fn exec(self) {
-    if let Err(error) = run_version(self, Self::get_pwd()) {
-      println!("{error:?}");
+  fn exec(self) -> Diagnostics {
+    let mut diagnostics = Diagnostics::default();
+    match run_version(self, Self::get_pwd(), &mut diagnostics) {
+      Err(VersionError::Config(errors)) => diagnostics.extend(errors.diagnostics()),
+      Err(error) => diagnostics.push(command_failed(&error)),
+      Ok(()) => {}
+    }
+    diagnostics
+  }
+}
+
+impl Exec for Tag {
+  fn exec(self) -> Diagnostics {
+    let mut diagnostics = Diagnostics::default();
+    match run_tag(self, Self::get_pwd()) {
+      Err(TagError::Config(errors)) => diagnostics.extend(errors.diagnostics()),
+      Err(error) => diagnostics.push(command_failed(&error)),
+      Ok(()) => {}
+    }
+    diagnostics
+  }
+}
+
+impl Exec for Config {
+  fn exec(self) -> Diagnostics {
+    let mut diagnostics = Diagnostics::default();
+    match run_config(self, Self::get_pwd()) {
+      Err(ConfigCommandError::Config(errors)) => diagnostics.extend(errors.diagnostics()),
+      Err(error) => diagnostics.push(command_failed(&error)),
+      Ok(()) => {}
+    }
+    diagnostics
+  }
+}
+
+impl Exec for Migrate {
+  fn exec(self) -> Diagnostics {
+    let mut diagnostics = Diagnostics::default();
+    if let Err(error) = run_migrate(self, Self::get_pwd()) {
+      diagnostics.push(command_failed(&error));
+    }
+    diagnostics
+  }
+}
+
+impl Exec for Graph {
+  fn exec(self) -> Diagnostics {
+    let mut diagnostics = Diagnostics::default();
+    match run_graph(self, Self::get_pwd()) {
+      Err(GraphError::Config(errors)) => diagnostics.extend(errors.diagnostics()),
+      Err(error) => diagnostics.push(command_failed(&error)),
+      Ok(()) => {}
+    }
+    diagnostics
+  }
+}
+
+impl Exec for FixRanges {
+  fn exec(self) -> Diagnostics {
+    let mut diagnostics = Diagnostics::default();
+    match run_fix_ranges(self, Self::get_pwd(), &mut diagnostics) {
+      Err(FixRangesError::Config(errors)) => diagnostics.extend(errors.diagnostics()),
+      Err(error) => diagnostics.push(command_failed(&error)),
+      Ok(()) => {}
+    }
+    diagnostics
+  }
+}
+
+impl Exec for Lint {
+  fn exec(self) -> Diagnostics {
+    let mut diagnostics = Diagnostics::default();
+    match run_lint(self, Self::get_pwd(), &mut diagnostics) {
+      Err(LintError::Config(errors)) => diagnostics.extend(errors.diagnostics()),
+      Err(error) => diagnostics.push(command_failed(&error)),
+      Ok(()) => {}
     }
+    diagnostics
   }
 }
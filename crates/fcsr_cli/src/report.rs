@@ -0,0 +1,88 @@
+use clap::ValueEnum;
+use colored::Colorize;
+use fcsr_diagnostic::{Diagnostic, Diagnostics, Severity};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Reporter {
+  Terminal,
+  Json,
+}
+
+impl Reporter {
+  pub fn report(self, diagnostics: &Diagnostics) {
+    match self {
+      Self::Terminal => eprint!("{}", render_terminal(diagnostics)),
+      Self::Json => println!("{}", render_json(diagnostics)),
+    }
+    if diagnostics.has_errors() {
+      std::process::exit(1);
+    }
+  }
+}
+
+pub fn command_failed(error: &dyn std::fmt::Display) -> Diagnostic {
+  Diagnostic::error("command-failed", error.to_string())
+}
+
+fn render_terminal(diagnostics: &Diagnostics) -> String {
+  let mut output = String::new();
+  for diagnostic in diagnostics.iter() {
+    let severity = match diagnostic.severity {
+      Severity::Error => "error".red().bold(),
+      Severity::Warning => "warning".yellow().bold(),
+    };
+    output.push_str(&format!(
+      "{severity}[{}]: {}\n",
+      diagnostic.code,
+      diagnostic.message.bold()
+    ));
+    if let Some(file) = &diagnostic.file {
+      output.push_str(&format!("  {} {}\n", "-->".blue(), file.display()));
+    }
+    if let Some(package) = &diagnostic.package {
+      output.push_str(&format!("  {} package: {}\n", "=".blue(), package.cyan()));
+    }
+    if let Some(help) = &diagnostic.help {
+      output.push_str(&format!("  {} help: {help}\n", "=".blue()));
+    }
+  }
+  output
+}
+
+fn render_json(diagnostics: &Diagnostics) -> String {
+  serde_json::to_string(diagnostics).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn diagnostics() -> Diagnostics {
+    Diagnostics::from_iter([
+      Diagnostic::error(
+        "graph/mismatched-range",
+        "Package \"a\" must depend on \"b\"",
+      )
+      .with_package("a")
+      .with_help("Update the range"),
+      Diagnostic::warning("config/read", "Something odd").with_file(".changeset/config.json"),
+    ])
+  }
+
+  #[test]
+  fn renders_terminal() {
+    colored::control::set_override(false);
+    assert_eq!(
+      render_terminal(&diagnostics()),
+      "error[graph/mismatched-range]: Package \"a\" must depend on \"b\"\n  = package: a\n  = help: Update the range\nwarning[config/read]: Something odd\n  --> .changeset/config.json\n"
+    );
+  }
+
+  #[test]
+  fn renders_json() {
+    assert_eq!(
+      render_json(&diagnostics()),
+      r#"[{"code":"graph/mismatched-range","severity":"error","message":"Package \"a\" must depend on \"b\"","package":"a","help":"Update the range"},{"code":"config/read","severity":"warning","message":"Something odd","file":".changeset/config.json"}]"#
+    );
+  }
+}
@@ -1,63 +1,135 @@
 use super::PackageGroup;
-use fcsr_pkg::access_type;
-use serde::{Deserialize, Serialize};
+use crate::written::{
+  PackageChangelog, PackageOverrides, PrivateChangesets, UpdateInternalDependencies,
+  UpdateInternalDependents,
+};
+use fcsr_pkg::access_type::AccessType;
+use glob::Pattern;
+use serde::Serialize;
+use std::collections::BTreeMap;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(untagged)]
 pub enum ConfigContainer {
   Bool(bool),
-  Tuple(String, String),
+  Tuple(String, Option<serde_json::Value>),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
 pub struct PrivatePackages {
-  version: bool,
-  tag: bool,
+  pub version: bool,
+  pub tag: bool,
+  pub changesets: PrivateChangesets,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
-pub enum UpdateInternalDependencies {
-  Patch,
-  Minor,
+pub struct Snapshot {
+  pub use_calculated_version: bool,
+  pub prerelease_template: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
-pub struct Snapshot {
-  use_calculated_version: bool,
-  prerelease_template: Option<String>,
+pub struct ExperimentalOptions {
+  pub only_update_peer_dependents_when_out_of_range: bool,
+  pub update_internal_dependents: UpdateInternalDependents,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case")]
-pub enum UpdateInternalDependents {
-  Always,
-  OutOfRange,
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TagPackage {
+  pub dir: String,
+  pub name: String,
+  pub tag_prefix: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct ExperimentalOptions {
-  only_update_peer_dependents_when_out_of_range: bool,
-  update_internal_dependents: UpdateInternalDependents,
-  use_calculated_version_for_snapshots: bool,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct WrittenConfig {
-  changelog: ConfigContainer,
-  commit: ConfigContainer,
-  fixed: Vec<PackageGroup>,
-  linked: Vec<PackageGroup>,
-  access: access_type::AccessType,
-  base_branch: String,
-  changed_file_patterns: PackageGroup,
-  private_packages: PrivatePackages,
-  update_internal_dependencies: UpdateInternalDependencies,
-  ignore: Vec<String>,
-  bump_version_with_workspace_protocol_only: Option<bool>,
-  #[serde(rename(serialize = "___experimentalUnsafeOptions_WILL_CHANGE_IN_PATCH"))]
-  experimental_unsafe_options_will_change_in_path: ExperimentalOptions,
-  snapshot: Snapshot,
+pub struct PackageSources {
+  pub python_packages: PackageGroup,
+  pub tag_packages: Vec<TagPackage>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyLint {
+  pub pin: BTreeMap<String, String>,
+  pub allow: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Lint {
+  pub deps: DependencyLint,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Config {
+  pub changelog: ConfigContainer,
+  pub commit: ConfigContainer,
+  pub fixed: Vec<PackageGroup>,
+  pub linked: Vec<PackageGroup>,
+  pub access: AccessType,
+  pub base_branch: String,
+  pub changed_file_patterns: PackageGroup,
+  pub private_packages: PrivatePackages,
+  pub update_internal_dependencies: UpdateInternalDependencies,
+  pub ignore: Vec<String>,
+  pub bump_versions_with_workspace_protocol_only: bool,
+  #[serde(rename = "___experimentalUnsafeOptions_WILL_CHANGE_IN_PATCH")]
+  pub experimental_unsafe_options: ExperimentalOptions,
+  pub snapshot: Snapshot,
+  #[serde(flatten)]
+  pub package_sources: PackageSources,
+  pub packages: BTreeMap<String, PackageOverrides>,
+  pub lint: Lint,
+}
+
+pub const DEFAULT_CHANGELOG_FILE: &str = "CHANGELOG.md";
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageOptions {
+  /// The changelog path relative to the package, `None` when it is disabled.
+  pub changelog: Option<String>,
+  pub tag_format: Option<String>,
+  pub publish: bool,
+}
+
+impl Config {
+  pub fn package_options(&self, name: &str) -> PackageOptions {
+    let mut options = PackageOptions {
+      changelog: (self.changelog != ConfigContainer::Bool(false))
+        .then(|| DEFAULT_CHANGELOG_FILE.to_string()),
+      tag_format: None,
+      publish: true,
+    };
+    let globs = self.packages.iter().filter(|(pattern, _)| {
+      *pattern != name && Pattern::new(pattern).is_ok_and(|glob| glob.matches(name))
+    });
+    let exact = self.packages.get_key_value(name);
+    for (_, overrides) in globs.chain(exact) {
+      match &overrides.changelog {
+        Some(PackageChangelog::Bool(false)) => options.changelog = None,
+        Some(PackageChangelog::Bool(true)) => {
+          options.changelog = Some(DEFAULT_CHANGELOG_FILE.to_string())
+        }
+        Some(PackageChangelog::Path(path)) => options.changelog = Some(path.clone()),
+        None => {}
+      }
+      if let Some(tag_format) = &overrides.tag_format {
+        options.tag_format = Some(tag_format.clone());
+      }
+      if let Some(publish) = overrides.publish {
+        options.publish = publish;
+      }
+    }
+    options
+  }
+}
+
+pub fn format_tag(format: &str, name: &str, version: &str) -> String {
+  format.replace("{name}", name).replace("{version}", version)
 }
@@ -1,10 +1,95 @@
-use colored::Colorize;
+use fcsr_diagnostic::{Diagnostic, Diagnostics};
 use fcsr_pkg::{
-  packages::{Package, Packages},
-  PkgJson,
+  npm_semver,
+  packages::{Package, PackageKind, Packages},
+  pep440, PkgJson,
 };
 use serde::{Deserialize, Serialize};
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{
+  cell::RefCell,
+  collections::{BTreeMap, BTreeSet, HashMap},
+  rc::Rc,
+};
+use thiserror::Error;
+
+const LOCAL_PROTOCOLS: [&str; 3] = ["link:", "file:", "portal:"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+  Registry,
+  Workspace,
+  /// `link:`, `file:` and `portal:` paths.
+  Local,
+  Other,
+}
+
+/// A dependency range with its protocol and `npm:` or `workspace:` alias resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DependencySpec<'a> {
+  /// The package the dependency points at, which differs from its key for aliases.
+  pub name: &'a str,
+  pub protocol: Protocol,
+  pub range: &'a str,
+  prefix: &'a str,
+}
+
+fn split_alias(spec: &str) -> Option<(&str, &str)> {
+  let at = spec.rfind('@').filter(|at| *at > 0)?;
+  Some((&spec[..at], &spec[at + 1..]))
+}
+
+impl<'a> DependencySpec<'a> {
+  pub fn parse(name: &'a str, range: &'a str) -> Self {
+    let (protocol, rest) = if let Some(rest) = range.strip_prefix("workspace:") {
+      (Protocol::Workspace, rest)
+    } else if let Some(rest) = range.strip_prefix("npm:") {
+      (Protocol::Registry, rest)
+    } else if let Some(protocol) = LOCAL_PROTOCOLS
+      .iter()
+      .find(|protocol| range.starts_with(*protocol))
+    {
+      (Protocol::Local, &range[protocol.len()..])
+    } else if is_protocol_range(range) {
+      (Protocol::Other, range)
+    } else {
+      (Protocol::Registry, range)
+    };
+
+    let aliased = rest.len() != range.len() && protocol != Protocol::Local;
+    match split_alias(rest) {
+      Some((name, rest)) if aliased => Self {
+        name,
+        protocol,
+        range: rest,
+        prefix: &range[..range.len() - rest.len()],
+      },
+      // `npm:name` without a range installs the latest release, just like a tag.
+      _ if protocol == Protocol::Registry && aliased => Self {
+        name: rest,
+        protocol,
+        range: "latest",
+        prefix: range,
+      },
+      _ => Self {
+        name,
+        protocol,
+        range: rest,
+        prefix: &range[..range.len() - rest.len()],
+      },
+    }
+  }
+
+  /// Whether the range always links the workspace package, like `workspace:^` or a workspace path.
+  pub fn links_any(&self) -> bool {
+    self.protocol == Protocol::Workspace
+      && (matches!(self.range, "*" | "^" | "~") || self.range.contains('/'))
+  }
+
+  /// Rebuilds the full range with `range` in place of the current one, keeping the protocol and alias.
+  pub fn with_range(&self, range: &str) -> String {
+    format!("{}{range}", self.prefix)
+  }
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -12,86 +97,206 @@ pub struct DependentsGraphOption {
   bump_versions_with_workspace_protocol_only: Option<bool>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum DependencyKind {
+  Dependencies,
+  DevDependencies,
+  PeerDependencies,
+  OptionalDependencies,
+}
+
+impl DependencyKind {
+  pub const ALL: [DependencyKind; 4] = [
+    DependencyKind::Dependencies,
+    DependencyKind::DevDependencies,
+    DependencyKind::PeerDependencies,
+    DependencyKind::OptionalDependencies,
+  ];
+
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      DependencyKind::Dependencies => "dependencies",
+      DependencyKind::DevDependencies => "devDependencies",
+      DependencyKind::PeerDependencies => "peerDependencies",
+      DependencyKind::OptionalDependencies => "optionalDependencies",
+    }
+  }
+
+  pub fn section(self, package_json: &PkgJson) -> Option<&HashMap<String, String>> {
+    match self {
+      DependencyKind::Dependencies => package_json.dependencies.as_ref(),
+      DependencyKind::DevDependencies => package_json.dev_dependencies.as_ref(),
+      DependencyKind::PeerDependencies => package_json.peer_dependencies.as_ref(),
+      DependencyKind::OptionalDependencies => package_json.optional_dependencies.as_ref(),
+    }
+  }
+}
+
+/// A dependency of `from` on the workspace package `to`, with the range as written.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Edge {
+  pub from: String,
+  pub to: String,
+  pub kind: DependencyKind,
+  pub range: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub struct DependencyGraphItem {
-  pkg: Package,
-  dependencies: Vec<String>,
+  pub pkg: Package,
+  pub dependencies: Vec<Edge>,
 }
 
 impl DependencyGraphItem {
-  fn new(pkg: Package, dependencies: Vec<String>) -> Self {
+  fn new(pkg: Package, dependencies: Vec<Edge>) -> Self {
     Self { pkg, dependencies }
   }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize)]
 pub struct DependencyGraph {
   graph: HashMap<String, DependencyGraphItem>,
-  valid: bool,
+  invalid: Vec<Edge>,
+  pub valid: bool,
+  pub diagnostics: Diagnostics,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct DependentGraph {
-  pkg: Package,
-  dependents: Vec<String>,
-}
+impl DependencyGraph {
+  pub fn get(&self, name: &str) -> Option<&DependencyGraphItem> {
+    self.graph.get(name)
+  }
 
-pub fn get_dependents_graph(
-  packages: Packages,
-  opts: Option<DependentsGraphOption>,
-) -> HashMap<String, Vec<String>> {
-  let mut graph = HashMap::new();
-  let DependencyGraph {
-    graph: dependency_graph,
-    ..
-  } = get_dependency_graph(&packages, opts);
-
-  let mut dependents_lookup = HashMap::new();
-  packages.packages.iter().for_each(|pkg| {
-    dependents_lookup.insert(
-      pkg.package_json.name.clone(),
-      DependentGraph {
-        pkg: pkg.clone(),
-        dependents: vec![],
-      },
-    );
-  });
+  /// Package names in the graph, sorted.
+  pub fn names(&self) -> Vec<&str> {
+    let mut names: Vec<&str> = self.graph.keys().map(String::as_str).collect();
+    names.sort();
+    names
+  }
+
+  pub fn edges(&self) -> impl Iterator<Item = &Edge> {
+    self
+      .names()
+      .into_iter()
+      .flat_map(|name| self.graph[name].dependencies.iter())
+  }
+
+  pub fn edges_of_kind<'a>(
+    &'a self,
+    kinds: &'a [DependencyKind],
+  ) -> impl Iterator<Item = &'a Edge> + 'a {
+    self.edges().filter(|edge| kinds.contains(&edge.kind))
+  }
+
+  /// Edges left out of the graph because their range doesn't include the current version.
+  pub fn invalid_edges(&self) -> &[Edge] {
+    &self.invalid
+  }
+
+  /// Edges from `name` to the packages it depends on.
+  pub fn dependencies<'a>(&'a self, name: &str) -> impl Iterator<Item = &'a Edge> + 'a {
+    self
+      .graph
+      .get(name)
+      .into_iter()
+      .flat_map(|item| item.dependencies.iter())
+  }
+
+  /// Edges from the packages depending on `name` to it.
+  pub fn dependents<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Edge> + 'a {
+    self.edges().filter(move |edge| edge.to == name)
+  }
 
-  packages.packages.iter().for_each(|pkg| {
-    let dependent = pkg.package_json.name.clone();
-    if let Some(val_from_dependency_graph) = dependency_graph.get(&dependent) {
-      val_from_dependency_graph
-        .dependencies
+  /// Groups packages into layers that only depend on packages in earlier layers.
+  pub fn topological_layers(&self, exclude_dev: bool) -> Result<Vec<Vec<String>>, DependencyCycle> {
+    let mut remaining: BTreeMap<&str, BTreeSet<&str>> = self
+      .names()
+      .into_iter()
+      .map(|name| {
+        let dependencies = self
+          .dependencies(name)
+          .filter(|edge| !(exclude_dev && edge.kind == DependencyKind::DevDependencies))
+          .map(|edge| edge.to.as_str())
+          .filter(|to| self.graph.contains_key(*to))
+          .collect();
+        (name, dependencies)
+      })
+      .collect();
+
+    let mut layers = vec![];
+    while !remaining.is_empty() {
+      let layer: Vec<&str> = remaining
         .iter()
-        .for_each(|dependency| {
-          dependents_lookup
-            .entry(dependency.clone())
-            .and_modify(|dependent_graph| {
-              dependent_graph.dependents.push(dependent.clone());
-            });
-        });
+        .filter(|(_, dependencies)| dependencies.is_empty())
+        .map(|(name, _)| *name)
+        .collect();
+      if layer.is_empty() {
+        return Err(find_cycle(&remaining));
+      }
+      for name in layer.iter() {
+        remaining.remove(name);
+      }
+      for dependencies in remaining.values_mut() {
+        dependencies.retain(|dependency| !layer.contains(dependency));
+      }
+      layers.push(layer.into_iter().map(String::from).collect());
     }
-  });
+    Ok(layers)
+  }
+}
 
-  for (key, value) in dependents_lookup {
-    graph.insert(key, value);
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("Found a dependency cycle: {}", .0.join(" -> "))]
+pub struct DependencyCycle(pub Vec<String>);
+
+/// Follows dependencies from the first package left until one repeats, every package
+/// left having at least one dependency left.
+fn find_cycle(remaining: &BTreeMap<&str, BTreeSet<&str>>) -> DependencyCycle {
+  let mut path: Vec<&str> = vec![];
+  let mut current = *remaining.keys().next().unwrap();
+  while !path.contains(&current) {
+    path.push(current);
+    current = remaining[current].iter().next().unwrap();
   }
+  let start = path.iter().position(|name| *name == current).unwrap();
+  let mut cycle: Vec<String> = path[start..].iter().map(|name| name.to_string()).collect();
+  cycle.push(current.to_string());
+  DependencyCycle(cycle)
+}
 
-  let mut simplified_dependents_graph = HashMap::new();
-  for (pkg_info, pkg_name) in graph {
-    simplified_dependents_graph.insert(pkg_info, pkg_name.dependents);
+pub fn get_dependents_graph(
+  packages: &Packages,
+  opts: Option<DependentsGraphOption>,
+) -> HashMap<String, Vec<String>> {
+  let dependency_graph = get_dependency_graph(packages, opts);
+
+  let mut dependents_graph: HashMap<String, Vec<String>> = packages
+    .packages
+    .iter()
+    .map(|pkg| (pkg.package_json.name.clone(), vec![]))
+    .collect();
+  for pkg in packages.packages.iter() {
+    for edge in dependency_graph.dependencies(&pkg.package_json.name) {
+      if let Some(dependents) = dependents_graph.get_mut(&edge.to) {
+        if !dependents.contains(&edge.from) {
+          dependents.push(edge.from.clone());
+        }
+      }
+    }
   }
 
-  simplified_dependents_graph
+  dependents_graph
 }
 
-fn get_dependency_graph(
+pub fn get_dependency_graph(
   packages: &Packages,
   opts: Option<DependentsGraphOption>,
 ) -> DependencyGraph {
   let mut dependency_graph = DependencyGraph {
     graph: HashMap::new(),
+    invalid: vec![],
     valid: true,
+    diagnostics: Diagnostics::default(),
   };
 
   let mut packages_by_name: HashMap<String, Package> = HashMap::new();
@@ -110,66 +315,73 @@ fn get_dependency_graph(
 
   for pkg in queue {
     let name = pkg.package_json.name.clone();
-    let dependencies: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(vec![]));
-    let all_dependencies = get_all_dependencies(pkg.package_json.clone());
+    let dependencies: Rc<RefCell<Vec<Edge>>> = Rc::new(RefCell::new(vec![]));
+    let all_dependencies = get_all_dependencies(&pkg.package_json);
 
-    for (dep_name, dep_range) in all_dependencies {
-      let rc_dependencies = Rc::clone(&dependencies);
+    for (kind, alias, written_range) in all_dependencies {
+      let dep_range = packages.resolve_range(&alias, &written_range);
+      let spec = DependencySpec::parse(&alias, dep_range);
+      let dep_name = spec.name.to_string();
       if let Some(dep_matched) = packages_by_name.get(&dep_name) {
-        let use_work_space_range = dep_range.starts_with("workspace:");
-        let expected = dep_matched.package_json.version.clone();
-
-        if use_work_space_range {
-          if let Ok(workspace_reg) = regex::Regex::new(r#"^workspace:"#) {
-            let new_dep_range = workspace_reg.replace(&dep_range, "");
-            if matches!(new_dep_range.to_string().as_str(), "*" | "^" | "~") {
-              rc_dependencies.borrow_mut().push(dep_name);
-              continue;
-            }
-          }
-        } else if let Some(DependentsGraphOption {
-          bump_versions_with_workspace_protocol_only,
-          ..
-        }) = opts
-        {
-          if bump_versions_with_workspace_protocol_only.is_some()
-            && bump_versions_with_workspace_protocol_only.unwrap()
-          {
-            continue;
-          }
+        if dep_matched.kind != pkg.kind {
+          continue;
         }
 
-        let mut effect_protocol_range = || {
+        let edge = Edge {
+          from: name.clone(),
+          to: dep_name.clone(),
+          kind,
+          range: written_range.clone(),
+        };
+        let expected = dep_matched.package_json.version.clone();
+        let mut mismatch = || {
           dependency_graph.valid = false;
-          println!(
-            r#"Package "{}" must depend on the current version of "{}": "{}" vs "{}""#,
-            name.cyan(),
-            dep_name.cyan(),
-            expected.green(),
-            dep_range.red()
-          );
+          dependency_graph.invalid.push(edge.clone());
+          dependency_graph
+            .diagnostics
+            .push(mismatched_range(&name, &dep_name, &expected, dep_range));
         };
 
-        let version_range = get_valid_range(&dep_range);
-        let expected_version = semver::Version::parse(&expected);
-
-        if (version_range.is_ok()
-          && expected_version.is_ok()
-          && !version_range
-            .clone()
-            .unwrap()
-            .matches(&expected_version.unwrap()))
-          || is_protocol_range(&dep_range)
-        {
-          effect_protocol_range();
+        if pkg.kind == PackageKind::Python {
+          match satisfies(PackageKind::Python, dep_range, &expected) {
+            Some(true) => dependencies.borrow_mut().push(edge),
+            Some(false) => mismatch(),
+            None => {}
+          }
           continue;
         }
 
-        if version_range.is_err() {
+        match spec.protocol {
+          Protocol::Workspace if spec.links_any() => {
+            dependencies.borrow_mut().push(edge);
+            continue;
+          }
+          Protocol::Workspace => {}
+          Protocol::Local | Protocol::Other => {
+            mismatch();
+            continue;
+          }
+          Protocol::Registry => {
+            if let Some(DependentsGraphOption {
+              bump_versions_with_workspace_protocol_only: Some(true),
+            }) = opts
+            {
+              continue;
+            }
+          }
+        }
+
+        let Ok(version_range) = get_valid_range(spec.range) else {
           continue;
+        };
+        if let Some(expected_version) = npm_semver::parse_version(&expected) {
+          if !version_range.satisfies(&expected_version) {
+            mismatch();
+            continue;
+          }
         }
 
-        dependencies.borrow_mut().push(dep_name);
+        dependencies.borrow_mut().push(edge);
       }
     }
 
@@ -182,77 +394,85 @@ fn get_dependency_graph(
   dependency_graph
 }
 
+pub fn satisfies(kind: PackageKind, range: &str, version: &str) -> Option<bool> {
+  match kind {
+    PackageKind::Npm => {
+      let spec = DependencySpec::parse("", range);
+      match spec.protocol {
+        Protocol::Workspace if spec.links_any() => return Some(true),
+        Protocol::Workspace | Protocol::Registry => {}
+        Protocol::Local | Protocol::Other => return None,
+      }
+      let version = npm_semver::parse_version(version)?;
+      get_valid_range(spec.range)
+        .ok()
+        .map(|version_range| version_range.satisfies(&version))
+    }
+    PackageKind::Python => {
+      let version = pep440::Version::parse(version).ok()?;
+      pep440::Specifiers::parse(range)
+        .ok()
+        .map(|specifiers| specifiers.contains(&version))
+    }
+    PackageKind::Tag => None,
+  }
+}
+
+fn mismatched_range(name: &str, dep_name: &str, expected: &str, dep_range: &str) -> Diagnostic {
+  Diagnostic::error(
+    "graph/mismatched-range",
+    format!(r#"Package "{name}" must depend on the current version of "{dep_name}": "{expected}" vs "{dep_range}""#),
+  )
+  .with_package(name)
+  .with_help(format!(
+    r#"Update the range of "{dep_name}" so that it includes {expected}"#
+  ))
+}
+
 fn is_protocol_range(range: &str) -> bool {
   range.contains(":")
 }
 
-fn get_valid_range(potential_range: &str) -> Result<semver::VersionReq, ()> {
+fn get_valid_range(potential_range: &str) -> Result<npm_semver::Range, ()> {
   if is_protocol_range(potential_range) {
     return Err(());
   }
 
-  match semver::VersionReq::parse(potential_range) {
-    Ok(version_req) => Ok(version_req),
-    Err(_) => Err(()),
-  }
+  npm_semver::Range::parse(potential_range).map_err(|_| ())
 }
 
-#[derive(Debug, Serialize, Eq, PartialEq)]
-#[serde(rename_all = "camelCase")]
-enum DependencyItems {
-  Dependencies,
-  DevDependencies,
-  PeerDependencies,
-  OptionalDependencies,
-}
-const ALL_DEPENDENCIES_ITEM: [DependencyItems; 4] = [
-  DependencyItems::Dependencies,
-  DependencyItems::DevDependencies,
-  DependencyItems::PeerDependencies,
-  DependencyItems::OptionalDependencies,
-];
-
-fn get_all_dependencies(config: PkgJson) -> HashMap<String, String> {
-  let mut all_dependencies = HashMap::new();
-
-  match serde_json::to_value(config.clone()) {
-    Ok(config_value) => match config_value {
-      serde_json::Value::Object(ref config_value_obj) => {
-        for dependency_item in ALL_DEPENDENCIES_ITEM {
-          if let Ok(dependency_item_string) = serde_json::to_string(&dependency_item) {
-            if let Some(dependency_item_value) =
-              config_value_obj.get(&dependency_item_string.replace("\"", ""))
-            {
-              match dependency_item_value {
-                serde_json::Value::Object(dependency_item_obj) => {
-                  for dependency_item_key in dependency_item_obj.keys() {
-                    match dependency_item_obj.get(dependency_item_key).unwrap() {
-                      serde_json::Value::String(dep_range) => {
-                        if (dep_range.starts_with("link:") || dep_range.starts_with("file:"))
-                          && dependency_item == DependencyItems::DevDependencies
-                        {
-                          continue;
-                        }
-
-                        all_dependencies.insert(dependency_item_key.clone(), dep_range.clone());
-                      }
-                      _ => {}
-                    }
-                  }
-                }
-                _ => continue,
-              }
-            }
-          }
-        }
+/// Every dependency of a package as `(kind, key, range)`, sorted by kind and key.
+/// pyproject.toml packages list every requirement, even when a name is required several times.
+pub fn get_all_dependencies(package_json: &PkgJson) -> Vec<(DependencyKind, String, String)> {
+  if !package_json.requirements.is_empty() {
+    let mut all_dependencies: Vec<_> = package_json
+      .requirements
+      .iter()
+      .map(|requirement| {
+        let kind = match requirement.optional {
+          true => DependencyKind::OptionalDependencies,
+          false => DependencyKind::Dependencies,
+        };
+        (kind, requirement.name.clone(), requirement.range.clone())
+      })
+      .collect();
+    all_dependencies.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+    return all_dependencies;
+  }
+
+  let mut all_dependencies = vec![];
+  for kind in DependencyKind::ALL {
+    let mut section: Vec<_> = kind.section(package_json).into_iter().flatten().collect();
+    section.sort();
+    for (name, range) in section {
+      if kind == DependencyKind::DevDependencies
+        && DependencySpec::parse(name, range).protocol == Protocol::Local
+      {
+        continue;
       }
-      _ => {}
-    },
-    Err(error) => {
-      println!("error: {error:?}")
+      all_dependencies.push((kind, name.clone(), range.clone()));
     }
   }
-
   all_dependencies
 }
 
@@ -265,6 +485,7 @@ mod test {
   macro_rules! gen_hash_map {
     (($($k:expr, $v:expr$(,)?)*)) => {
       {
+        #[allow(unused_mut)]
         let mut hash_map = HashMap::new();
         $(hash_map.insert($k, $v);)*
         hash_map
@@ -272,6 +493,14 @@ mod test {
     };
   }
 
+  fn targets<'a>(graph: &'a HashMap<String, DependencyGraphItem>, name: &str) -> Vec<&'a str> {
+    graph[name]
+      .dependencies
+      .iter()
+      .map(|edge| edge.to.as_str())
+      .collect()
+  }
+
   #[test]
   fn test_get_all_dependencies() {
     let pkg_json = PkgJson {
@@ -279,15 +508,34 @@ mod test {
       version: String::from("1.1.1"),
       dependencies: Some(gen_hash_map!(("A".to_string(), "a".to_string()))),
       dev_dependencies: Some(gen_hash_map!(("B".to_string(), "b".to_string()))),
-      peer_dependencies: Some(gen_hash_map!(("C".to_string(), "c".to_string()))),
+      peer_dependencies: Some(gen_hash_map!((
+        "B".to_string(),
+        "^b".to_string(),
+        "C".to_string(),
+        "c".to_string()
+      ))),
       optional_dependencies: Some(gen_hash_map!(("D".to_string(), "d".to_string()))),
       resolutions: Some(gen_hash_map!(())),
       private: None,
       publish_config: None,
       workspaces: None,
+      requirements: vec![],
     };
 
-    println!("{:?}", get_all_dependencies(pkg_json))
+    let all_dependencies: Vec<_> = get_all_dependencies(&pkg_json)
+      .into_iter()
+      .map(|(kind, name, range)| format!("{}:{name}@{range}", kind.as_str()))
+      .collect();
+    assert_eq!(
+      all_dependencies,
+      vec![
+        "dependencies:A@a",
+        "devDependencies:B@b",
+        "peerDependencies:B@^b",
+        "peerDependencies:C@c",
+        "optionalDependencies:D@d",
+      ]
+    );
   }
 
   #[test]
@@ -296,8 +544,10 @@ mod test {
       root: Package {
         package_json: PkgJson::new("root".to_string(), "1.0.0".to_string()),
         dir: PathBuf::from("."),
+        kind: PackageKind::Npm,
       },
       tool: fcsr_pkg::packages::Tool::Pnpm,
+      catalogs: Default::default(),
       packages: vec![
         Package {
           dir: PathBuf::from("foo"),
@@ -315,15 +565,18 @@ mod test {
             private: None,
             publish_config: None,
             workspaces: None,
+            requirements: vec![],
           },
+          kind: PackageKind::Npm,
         },
         Package {
           dir: PathBuf::from("bar"),
           package_json: PkgJson::new("bar".to_string(), "1.0.0".to_string()),
+          kind: PackageKind::Npm,
         },
       ],
     };
-    let DependencyGraph { valid, graph } = get_dependency_graph(&pkg, None);
+    let DependencyGraph { valid, graph, .. } = get_dependency_graph(&pkg, None);
 
     assert!(valid);
     assert_eq!(
@@ -336,9 +589,11 @@ mod test {
   fn should_skip_dependencies_specified_using_a_tag() {
     let pkg = Packages {
       tool: fcsr_pkg::packages::Tool::Pnpm,
+      catalogs: Default::default(),
       root: Package {
         package_json: PkgJson::new("root".to_string(), "1.0.0".to_string()),
         dir: PathBuf::from("."),
+        kind: PackageKind::Npm,
       },
       packages: vec![
         Package {
@@ -354,15 +609,18 @@ mod test {
             private: None,
             publish_config: None,
             workspaces: None,
+            requirements: vec![],
           },
+          kind: PackageKind::Npm,
         },
         Package {
           dir: PathBuf::from("packages/bar"),
           package_json: PkgJson::new("bar".to_string(), "1.0.0".to_string()),
+          kind: PackageKind::Npm,
         },
       ],
     };
-    let DependencyGraph { valid, graph } = get_dependency_graph(&pkg, None);
+    let DependencyGraph { valid, graph, .. } = get_dependency_graph(&pkg, None);
 
     assert!(valid);
     assert_eq!(
@@ -377,8 +635,10 @@ mod test {
       root: Package {
         package_json: PkgJson::new("root".to_string(), "1.0.0".to_string()),
         dir: PathBuf::from("."),
+        kind: PackageKind::Npm,
       },
       tool: fcsr_pkg::packages::Tool::Pnpm,
+      catalogs: Default::default(),
       packages: vec![
         Package {
           dir: PathBuf::from("foo"),
@@ -396,16 +656,284 @@ mod test {
             private: None,
             publish_config: None,
             workspaces: None,
+            requirements: vec![],
           },
+          kind: PackageKind::Npm,
         },
         Package {
           dir: PathBuf::from("bar"),
           package_json: PkgJson::new("bar".to_string(), "1.0.0".to_string()),
+          kind: PackageKind::Npm,
         },
       ],
     };
-    let DependencyGraph { valid, .. } = get_dependency_graph(&pkg, None);
+    let DependencyGraph {
+      valid, diagnostics, ..
+    } = get_dependency_graph(&pkg, None);
+
+    assert!(!valid);
+    let diagnostic = diagnostics.iter().next().unwrap();
+    assert_eq!(diagnostic.code, "graph/mismatched-range");
+    assert_eq!(diagnostic.package.as_deref(), Some("foo"));
+  }
+
+  #[test]
+  fn should_check_python_dependencies_with_pep440_specifiers() {
+    let python_package = |name: &str, version: &str, dependencies| Package {
+      dir: PathBuf::from(name),
+      package_json: PkgJson {
+        dependencies,
+        ..PkgJson::new(name.to_string(), version.to_string())
+      },
+      kind: PackageKind::Python,
+    };
+    let pkg = Packages {
+      tool: fcsr_pkg::packages::Tool::Root,
+      catalogs: Default::default(),
+      root: Package {
+        package_json: PkgJson::new("root".to_string(), "1.0.0".to_string()),
+        dir: PathBuf::from("."),
+        kind: PackageKind::Npm,
+      },
+      packages: vec![
+        python_package(
+          "client",
+          "1.0.0",
+          Some(gen_hash_map!((
+            "core".to_string(),
+            ">=0.4,<0.5".to_string()
+          ))),
+        ),
+        python_package(
+          "cli",
+          "1.0.0",
+          Some(gen_hash_map!(("core".to_string(), "~=0.3.0".to_string()))),
+        ),
+        python_package("core", "0.4.2", None),
+      ],
+    };
+    let DependencyGraph { valid, graph, .. } = get_dependency_graph(&pkg, None);
 
     assert!(!valid);
+    assert_eq!(targets(&graph, "client"), vec!["core"]);
+    assert!(graph.get("cli").unwrap().dependencies.is_empty());
+  }
+
+  #[test]
+  fn should_resolve_pnpm_catalog_ranges() {
+    let pkg = Packages {
+      tool: fcsr_pkg::packages::Tool::Pnpm,
+      catalogs: gen_hash_map!((
+        "default".to_string(),
+        gen_hash_map!(("bar".to_string(), "^1.0.0".to_string()))
+      )),
+      root: Package {
+        package_json: PkgJson::new("root".to_string(), "1.0.0".to_string()),
+        dir: PathBuf::from("."),
+        kind: PackageKind::Npm,
+      },
+      packages: vec![
+        Package {
+          dir: PathBuf::from("foo"),
+          package_json: PkgJson {
+            dependencies: Some(gen_hash_map!(("bar".to_string(), "catalog:".to_string()))),
+            ..PkgJson::new("foo".to_string(), "1.0.0".to_string())
+          },
+          kind: PackageKind::Npm,
+        },
+        Package {
+          dir: PathBuf::from("bar"),
+          package_json: PkgJson::new("bar".to_string(), "1.2.0".to_string()),
+          kind: PackageKind::Npm,
+        },
+      ],
+    };
+    let DependencyGraph { valid, graph, .. } = get_dependency_graph(&pkg, None);
+
+    assert!(valid);
+    assert_eq!(targets(&graph, "foo"), vec!["bar"]);
+  }
+
+  #[test]
+  fn should_check_workspace_ranges_and_aliases() {
+    let npm_package = |name: &str, version: &str, dependencies, dev_dependencies| Package {
+      dir: PathBuf::from(name),
+      package_json: PkgJson {
+        dependencies,
+        dev_dependencies,
+        ..PkgJson::new(name.to_string(), version.to_string())
+      },
+      kind: PackageKind::Npm,
+    };
+    let pkg = Packages {
+      tool: fcsr_pkg::packages::Tool::Pnpm,
+      catalogs: Default::default(),
+      root: npm_package("root", "1.0.0", None, None),
+      packages: vec![
+        npm_package(
+          "foo",
+          "1.0.0",
+          Some(gen_hash_map!((
+            "bar".to_string(),
+            "workspace:^1.2.0".to_string(),
+            "baz-alias".to_string(),
+            "npm:@scope/baz@~2.0.0".to_string(),
+          ))),
+          Some(gen_hash_map!((
+            "qux".to_string(),
+            "portal:../qux".to_string()
+          ))),
+        ),
+        npm_package(
+          "other",
+          "1.0.0",
+          Some(gen_hash_map!((
+            "bar".to_string(),
+            "workspace:1.2.0".to_string()
+          ))),
+          None,
+        ),
+        npm_package("bar", "1.2.3", None, None),
+        npm_package("@scope/baz", "2.0.1", None, None),
+        npm_package("qux", "1.0.0", None, None),
+      ],
+    };
+    let DependencyGraph {
+      valid,
+      graph,
+      diagnostics,
+      ..
+    } = get_dependency_graph(&pkg, None);
+
+    assert_eq!(targets(&graph, "foo"), vec!["bar", "@scope/baz"]);
+    assert!(!valid);
+    let diagnostic = diagnostics.iter().next().unwrap();
+    assert_eq!(diagnostic.package.as_deref(), Some("other"));
+    assert!(DependencySpec::parse("bar", "workspace:../bar").links_any());
+    assert_eq!(
+      satisfies(PackageKind::Npm, "workspace:^1.2.0", "2.0.0"),
+      Some(false)
+    );
+    assert_eq!(satisfies(PackageKind::Npm, "link:../bar", "1.0.0"), None);
+    assert_eq!(
+      satisfies(PackageKind::Npm, "1.x || >=2.5.0", "2.6.0"),
+      Some(true)
+    );
+    assert_eq!(
+      satisfies(PackageKind::Npm, "1.2 - 2.3", "2.3.9"),
+      Some(true)
+    );
+    assert_eq!(satisfies(PackageKind::Npm, "latest", "1.0.0"), None);
+  }
+
+  #[test]
+  fn should_keep_an_edge_per_dependency_kind() {
+    let pkg = Packages {
+      tool: fcsr_pkg::packages::Tool::Pnpm,
+      catalogs: Default::default(),
+      root: Package {
+        package_json: PkgJson::new("root".to_string(), "1.0.0".to_string()),
+        dir: PathBuf::from("."),
+        kind: PackageKind::Npm,
+      },
+      packages: vec![
+        Package {
+          dir: PathBuf::from("foo"),
+          package_json: PkgJson {
+            dev_dependencies: Some(gen_hash_map!((
+              "bar".to_string(),
+              "workspace:*".to_string()
+            ))),
+            peer_dependencies: Some(gen_hash_map!(("bar".to_string(), "^1.0.0".to_string()))),
+            ..PkgJson::new("foo".to_string(), "1.0.0".to_string())
+          },
+          kind: PackageKind::Npm,
+        },
+        Package {
+          dir: PathBuf::from("bar"),
+          package_json: PkgJson::new("bar".to_string(), "1.2.0".to_string()),
+          kind: PackageKind::Npm,
+        },
+      ],
+    };
+    let graph = get_dependency_graph(&pkg, None);
+
+    let kinds: Vec<_> = graph.dependents("bar").map(|edge| edge.kind).collect();
+    assert_eq!(
+      kinds,
+      vec![
+        DependencyKind::DevDependencies,
+        DependencyKind::PeerDependencies
+      ]
+    );
+    let peers: Vec<_> = graph
+      .edges_of_kind(&[DependencyKind::PeerDependencies])
+      .collect();
+    assert_eq!(
+      peers,
+      vec![&Edge {
+        from: "foo".to_string(),
+        to: "bar".to_string(),
+        kind: DependencyKind::PeerDependencies,
+        range: "^1.0.0".to_string(),
+      }]
+    );
+    assert_eq!(graph.dependencies("bar").count(), 0);
+  }
+
+  #[test]
+  fn should_order_packages_in_layers() {
+    let npm_package = |name: &str, dependencies: &[(&str, DependencyKind)]| {
+      let mut package_json = PkgJson::new(name.to_string(), "1.0.0".to_string());
+      for (dependency, kind) in dependencies {
+        let section = match kind {
+          DependencyKind::DevDependencies => &mut package_json.dev_dependencies,
+          _ => &mut package_json.dependencies,
+        };
+        section
+          .get_or_insert_with(HashMap::new)
+          .insert(dependency.to_string(), "^1.0.0".to_string());
+      }
+      Package {
+        dir: PathBuf::from(name),
+        package_json,
+        kind: PackageKind::Npm,
+      }
+    };
+    let mut pkg = Packages {
+      tool: fcsr_pkg::packages::Tool::Pnpm,
+      catalogs: Default::default(),
+      root: npm_package("root", &[("app", DependencyKind::DevDependencies)]),
+      packages: vec![
+        npm_package("app", &[("ui", DependencyKind::Dependencies)]),
+        npm_package(
+          "ui",
+          &[
+            ("core", DependencyKind::Dependencies),
+            ("test-utils", DependencyKind::DevDependencies),
+          ],
+        ),
+        npm_package("core", &[]),
+        npm_package("test-utils", &[("ui", DependencyKind::Dependencies)]),
+      ],
+    };
+
+    let graph = get_dependency_graph(&pkg, None);
+    assert_eq!(
+      graph.topological_layers(false).unwrap_err().to_string(),
+      "Found a dependency cycle: ui -> test-utils -> ui"
+    );
+    assert_eq!(
+      graph.topological_layers(true).unwrap(),
+      vec![vec!["core", "root"], vec!["ui"], vec!["app", "test-utils"],]
+    );
+
+    pkg.packages[2] = npm_package("core", &[("app", DependencyKind::Dependencies)]);
+    assert_eq!(
+      get_dependency_graph(&pkg, None).topological_layers(true),
+      Err(DependencyCycle(
+        ["app", "ui", "core", "app"].map(String::from).to_vec()
+      ))
+    );
   }
 }
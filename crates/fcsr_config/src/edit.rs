@@ -0,0 +1,305 @@
+use crate::{
+  schema::{config_schema, validate, SchemaError},
+  source::{ConfigSource, PACKAGE_JSON_KEY},
+  written::{ReadConfigError, WrittenConfig},
+};
+use fcsr_pkg::json_edit::{JsonDocument, JsonEditError};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use toml_edit::{DocumentMut, Item};
+
+#[derive(Debug, Error)]
+pub enum EditError {
+  #[error(transparent)]
+  Read(#[from] ReadConfigError),
+  #[error("Invalid config path `{0}`")]
+  InvalidPath(String),
+  #[error("`{0}` is not set")]
+  NotSet(String),
+  #[error("`{0}` is not an object or array")]
+  NotAContainer(String),
+  #[error("Invalid value: {}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+  Invalid(Vec<SchemaError>),
+  #[error("Failed to edit {0}: {1}")]
+  Jsonc(PathBuf, JsonEditError),
+  #[error("Failed to edit {0}: {1}")]
+  Toml(PathBuf, Box<toml_edit::TomlError>),
+  #[error("Failed to write {0}: {1}")]
+  Io(PathBuf, std::io::Error),
+}
+
+/// Splits a path like `$.fixed[0]` or `snapshot.prereleaseTemplate` into its keys.
+pub fn parse_path(path: &str) -> Result<Vec<String>, EditError> {
+  let invalid = || EditError::InvalidPath(path.to_string());
+  let mut rest = path.strip_prefix('$').unwrap_or(path);
+  rest = rest.strip_prefix('.').unwrap_or(rest);
+  let mut keys = vec![];
+  while !rest.is_empty() {
+    if let Some(bracket) = rest.strip_prefix('[') {
+      let end = bracket.find(']').ok_or_else(invalid)?;
+      let key = &bracket[..end];
+      let key = match key.chars().next() {
+        Some(quote @ ('"' | '\'')) => key
+          .strip_prefix(quote)
+          .and_then(|key| key.strip_suffix(quote))
+          .ok_or_else(invalid)?,
+        _ if !key.is_empty() && key.chars().all(|char| char.is_ascii_digit()) => key,
+        _ => return Err(invalid()),
+      };
+      keys.push(key.to_string());
+      rest = &bracket[end + 1..];
+    } else {
+      let end = rest.find(['.', '[']).unwrap_or(rest.len());
+      if end == 0 {
+        return Err(invalid());
+      }
+      keys.push(rest[..end].to_string());
+      rest = &rest[end..];
+    }
+    if let Some(next) = rest.strip_prefix('.') {
+      if next.is_empty() || next.starts_with('[') {
+        return Err(invalid());
+      }
+      rest = next;
+    }
+  }
+  if keys.is_empty() {
+    return Err(invalid());
+  }
+  Ok(keys)
+}
+
+fn lookup<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+  match value {
+    Value::Object(object) => object.get(key),
+    Value::Array(items) => key.parse().ok().and_then(|index: usize| items.get(index)),
+    _ => None,
+  }
+}
+
+pub fn get_config_value(root: &Path, path: &[String]) -> Result<Value, EditError> {
+  let (_, value) = ConfigSource::locate(root)?;
+  path
+    .iter()
+    .try_fold(&value, |value, key| lookup(value, key))
+    .cloned()
+    .ok_or_else(|| EditError::NotSet(path.join(".")))
+}
+
+fn set_in(target: &mut Value, path: &[String], value: Value) -> Result<(), EditError> {
+  let mut target = target;
+  for (depth, key) in path.iter().enumerate() {
+    if target.is_null() {
+      if key.parse::<usize>().is_ok() {
+        return Err(EditError::NotSet(path[..=depth].join(".")));
+      }
+      *target = Value::Object(Default::default());
+    }
+    target = match target {
+      Value::Object(object) => object.entry(key.as_str()).or_insert(Value::Null),
+      Value::Array(items) => key
+        .parse()
+        .ok()
+        .and_then(|index: usize| items.get_mut(index))
+        .ok_or_else(|| EditError::NotSet(path[..=depth].join(".")))?,
+      _ => return Err(EditError::NotAContainer(path[..depth].join("."))),
+    };
+  }
+  *target = value;
+  Ok(())
+}
+
+fn toml_value(value: &Value) -> Option<toml_edit::Value> {
+  Some(match value {
+    Value::Null => return None,
+    Value::Bool(value) => (*value).into(),
+    Value::Number(number) => match number.as_i64() {
+      Some(number) => number.into(),
+      None => number.as_f64().unwrap_or_default().into(),
+    },
+    Value::String(value) => value.as_str().into(),
+    Value::Array(items) => toml_edit::Value::Array(items.iter().filter_map(toml_value).collect()),
+    Value::Object(object) => toml_edit::Value::InlineTable(
+      object
+        .iter()
+        .filter_map(|(key, value)| Some((key.as_str(), toml_value(value)?)))
+        .collect(),
+    ),
+  })
+}
+
+fn set_toml(document: &mut DocumentMut, path: &[String], config: &Value) {
+  // Only tables are followed, so editing an array element rewrites the whole array.
+  let mut depth = 0;
+  let mut item = document.as_item();
+  while depth + 1 < path.len() {
+    match item.get(path[depth].as_str()) {
+      Some(next) if next.is_table_like() => {
+        item = next;
+        depth += 1;
+      }
+      _ => break,
+    }
+  }
+  let value = path[..=depth]
+    .iter()
+    .try_fold(config, |value, key| lookup(value, key))
+    .unwrap_or(&Value::Null);
+
+  let mut item = document.as_item_mut();
+  for key in &path[..depth] {
+    let Some(next) = item.get_mut(key.as_str()) else {
+      return;
+    };
+    item = next;
+  }
+  let Some(table) = item.as_table_like_mut() else {
+    return;
+  };
+  let key = path[depth].as_str();
+  match toml_value(value) {
+    None => {
+      table.remove(key);
+    }
+    Some(toml_edit::Value::InlineTable(inline)) if table.get(key).is_some_and(Item::is_table) => {
+      table.insert(key, Item::Table(inline.into_table()));
+    }
+    Some(mut value) => match table.get_mut(key) {
+      Some(Item::Value(existing)) => {
+        *value.decor_mut() = existing.decor().clone();
+        *existing = value;
+      }
+      _ => {
+        table.insert(key, Item::Value(value));
+      }
+    },
+  }
+}
+
+/// Sets `path` to `value` after validating the result, keeping the file's formatting.
+pub fn set_config_value(root: &Path, path: &[String], value: Value) -> Result<(), EditError> {
+  let (source, mut config) = ConfigSource::locate(root)?;
+  let file = source.path(root);
+  set_in(&mut config, path, value.clone())?;
+  let errors = validate(&config_schema(), &config);
+  if !errors.is_empty() {
+    return Err(EditError::Invalid(errors));
+  }
+  serde_json::from_value::<WrittenConfig>(config.clone())
+    .map_err(|error| ReadConfigError::Json(file.clone(), error))?;
+
+  let text = std::fs::read_to_string(&file).map_err(|error| EditError::Io(file.clone(), error))?;
+  let text = match source {
+    ConfigSource::Toml => {
+      let mut document: DocumentMut = text
+        .parse()
+        .map_err(|error| EditError::Toml(file.clone(), Box::new(error)))?;
+      set_toml(&mut document, path, &config);
+      document.to_string()
+    }
+    _ => {
+      let mut keys: Vec<&str> = path.iter().map(String::as_str).collect();
+      if source == ConfigSource::PackageJson {
+        keys.insert(0, PACKAGE_JSON_KEY);
+      }
+      let mut document =
+        JsonDocument::parse(&text).map_err(|error| EditError::Jsonc(file.clone(), error))?;
+      document
+        .set(&keys, &value)
+        .map_err(|error| EditError::Jsonc(file.clone(), error))?;
+      document.to_string()
+    }
+  };
+  std::fs::write(&file, text).map_err(|error| EditError::Io(file, error))
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use serde_json::json;
+
+  fn keys(path: &str) -> Vec<String> {
+    parse_path(path).unwrap()
+  }
+
+  #[test]
+  fn parses_paths() {
+    assert_eq!(keys("baseBranch"), vec!["baseBranch"]);
+    assert_eq!(keys("$.fixed[0][1]"), vec!["fixed", "0", "1"]);
+    assert_eq!(
+      keys("snapshot['prereleaseTemplate']"),
+      vec!["snapshot", "prereleaseTemplate"]
+    );
+    assert_eq!(keys("$[\"$schema\"]"), vec!["$schema"]);
+    for invalid in ["", "$", "a.", "a..b", "a[x]", "a[0"] {
+      assert!(parse_path(invalid).is_err(), "{invalid}");
+    }
+  }
+
+  #[test]
+  fn sets_values_preserving_format() {
+    let cases = [
+      (
+        ".changeset/config.jsonc",
+        "{\n  // release from main\n  \"baseBranch\": \"main\",\n  \"fixed\": [[\"a\", \"b\"]],\n}\n",
+        "{\n  // release from main\n  \"baseBranch\": \"next\",\n  \"fixed\": [[\"a\", \"c\"]],\n  \"snapshot\": {\n    \"useCalculatedVersion\": true\n  },\n}\n",
+      ),
+      (
+        ".changeset/config.toml",
+        "# release from main\nbaseBranch = \"main\"\nfixed = [[\"a\", \"b\"]]\n",
+        "# release from main\nbaseBranch = \"next\"\nfixed = [[\"a\", \"c\"]]\nsnapshot = { useCalculatedVersion = true }\n",
+      ),
+      (
+        "package.json",
+        "{\n  \"name\": \"root\",\n  \"changeset\": {\n    \"baseBranch\": \"main\",\n    \"fixed\": [[\"a\", \"b\"]]\n  }\n}\n",
+        "{\n  \"name\": \"root\",\n  \"changeset\": {\n    \"baseBranch\": \"next\",\n    \"fixed\": [[\"a\", \"c\"]],\n    \"snapshot\": {\n      \"useCalculatedVersion\": true\n    }\n  }\n}\n",
+      ),
+    ];
+    for (file, source, expected) in cases {
+      let dir = tempfile::tempdir().unwrap();
+      let root = dir.path();
+      std::fs::create_dir_all(root.join(".changeset")).unwrap();
+      std::fs::write(root.join(file), source).unwrap();
+
+      set_config_value(root, &keys("baseBranch"), json!("next")).unwrap();
+      set_config_value(root, &keys("fixed[0][1]"), json!("c")).unwrap();
+      set_config_value(root, &keys("snapshot.useCalculatedVersion"), json!(true)).unwrap();
+      assert_eq!(
+        std::fs::read_to_string(root.join(file)).unwrap(),
+        expected,
+        "{file}"
+      );
+      assert_eq!(
+        get_config_value(root, &keys("fixed[0]")).unwrap(),
+        json!(["a", "c"])
+      );
+    }
+  }
+
+  #[test]
+  fn rejects_invalid_values() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    std::fs::create_dir_all(root.join(".changeset")).unwrap();
+    std::fs::write(root.join(".changeset/config.json"), "{}").unwrap();
+
+    let error = set_config_value(root, &keys("access"), json!("everyone")).unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      "Invalid value: `access` should be one of \"public\", \"restricted\", \"private\", found \"everyone\""
+    );
+    assert!(matches!(
+      set_config_value(root, &keys("fixed[0]"), json!(["a"])),
+      Err(EditError::NotSet(_))
+    ));
+    assert!(matches!(
+      get_config_value(root, &keys("baseBranch")),
+      Err(EditError::NotSet(_))
+    ));
+    assert_eq!(
+      std::fs::read_to_string(root.join(".changeset/config.json")).unwrap(),
+      "{}"
+    );
+  }
+}
@@ -0,0 +1,176 @@
+use crate::{
+  schema::{config_schema, validate},
+  written::ReadConfigError,
+};
+use serde_json::{json, Map, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvKind {
+  String,
+  Bool,
+  /// A comma separated list.
+  List,
+  /// `false`, `true` or a module name.
+  Container,
+}
+
+#[derive(Debug)]
+pub struct EnvOverride {
+  pub var: &'static str,
+  pub path: &'static [&'static str],
+  pub kind: EnvKind,
+}
+
+pub const ENV_OVERRIDES: [EnvOverride; 10] = [
+  EnvOverride {
+    var: "FCSR_BASE_BRANCH",
+    path: &["baseBranch"],
+    kind: EnvKind::String,
+  },
+  EnvOverride {
+    var: "FCSR_ACCESS",
+    path: &["access"],
+    kind: EnvKind::String,
+  },
+  EnvOverride {
+    var: "FCSR_CHANGELOG",
+    path: &["changelog"],
+    kind: EnvKind::Container,
+  },
+  EnvOverride {
+    var: "FCSR_COMMIT",
+    path: &["commit"],
+    kind: EnvKind::Container,
+  },
+  EnvOverride {
+    var: "FCSR_IGNORE",
+    path: &["ignore"],
+    kind: EnvKind::List,
+  },
+  EnvOverride {
+    var: "FCSR_CHANGED_FILE_PATTERNS",
+    path: &["changedFilePatterns"],
+    kind: EnvKind::List,
+  },
+  EnvOverride {
+    var: "FCSR_UPDATE_INTERNAL_DEPENDENCIES",
+    path: &["updateInternalDependencies"],
+    kind: EnvKind::String,
+  },
+  EnvOverride {
+    var: "FCSR_BUMP_VERSIONS_WITH_WORKSPACE_PROTOCOL_ONLY",
+    path: &["bumpVersionsWithWorkspaceProtocolOnly"],
+    kind: EnvKind::Bool,
+  },
+  EnvOverride {
+    var: "FCSR_SNAPSHOT_TEMPLATE",
+    path: &["snapshot", "prereleaseTemplate"],
+    kind: EnvKind::String,
+  },
+  EnvOverride {
+    var: "FCSR_SNAPSHOT_USE_CALCULATED_VERSION",
+    path: &["snapshot", "useCalculatedVersion"],
+    kind: EnvKind::Bool,
+  },
+];
+
+impl EnvOverride {
+  fn value(&self, raw: &str) -> Result<Value, String> {
+    let bool = |raw: &str| match raw {
+      "true" | "1" => Some(true),
+      "false" | "0" => Some(false),
+      _ => None,
+    };
+    let value = match self.kind {
+      EnvKind::String => json!(raw),
+      EnvKind::Bool => json!(bool(raw).ok_or(format!("should be true or false, found \"{raw}\""))?),
+      EnvKind::List => raw
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .collect(),
+      EnvKind::Container => bool(raw).map_or_else(|| json!(raw), Value::Bool),
+    };
+    let config = self.path.iter().rev().fold(value, |value, key| {
+      Value::Object(Map::from_iter([(key.to_string(), value)]))
+    });
+    match validate(&config_schema(), &config).first() {
+      Some(error) => Err(error.message.clone()),
+      None => Ok(config),
+    }
+  }
+}
+
+/// Reads every set override as a config object holding only that option, in mapping order.
+pub fn env_layers(
+  env: impl Fn(&str) -> Option<String>,
+) -> Result<Vec<(&'static str, Value)>, ReadConfigError> {
+  ENV_OVERRIDES
+    .iter()
+    .filter_map(|mapping| env(mapping.var).map(|raw| (mapping, raw)))
+    .map(|(mapping, raw)| {
+      mapping
+        .value(&raw)
+        .map(|value| (mapping.var, value))
+        .map_err(|message| ReadConfigError::Env(mapping.var, message))
+    })
+    .collect()
+}
+
+pub fn read_env(var: &str) -> Option<String> {
+  std::env::var(var).ok().filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn layers(vars: &[(&str, &str)]) -> Result<Vec<(&'static str, Value)>, ReadConfigError> {
+    env_layers(|var| {
+      vars
+        .iter()
+        .find(|(name, _)| *name == var)
+        .map(|(_, value)| value.to_string())
+    })
+  }
+
+  #[test]
+  fn converts_env_values() {
+    assert_eq!(
+      layers(&[
+        ("FCSR_SNAPSHOT_TEMPLATE", "{tag}-{commit}"),
+        ("FCSR_IGNORE", "docs, examples"),
+        ("FCSR_COMMIT", "true"),
+        ("FCSR_CHANGELOG", "@changesets/changelog-github"),
+        ("FCSR_UNKNOWN", "x"),
+      ])
+      .unwrap(),
+      vec![
+        (
+          "FCSR_CHANGELOG",
+          json!({"changelog": "@changesets/changelog-github"})
+        ),
+        ("FCSR_COMMIT", json!({"commit": true})),
+        ("FCSR_IGNORE", json!({"ignore": ["docs", "examples"]})),
+        (
+          "FCSR_SNAPSHOT_TEMPLATE",
+          json!({"snapshot": {"prereleaseTemplate": "{tag}-{commit}"}})
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn type_checks_env_values() {
+    assert_eq!(
+      layers(&[("FCSR_ACCESS", "everyone")]).unwrap_err().to_string(),
+      "Invalid FCSR_ACCESS: should be one of \"public\", \"restricted\", \"private\", found \"everyone\""
+    );
+    assert_eq!(
+      layers(&[("FCSR_SNAPSHOT_USE_CALCULATED_VERSION", "yes")])
+        .unwrap_err()
+        .to_string(),
+      "Invalid FCSR_SNAPSHOT_USE_CALCULATED_VERSION: should be true or false, found \"yes\""
+    );
+  }
+}
@@ -0,0 +1,203 @@
+use crate::{
+  schema::{config_schema, validate},
+  written::ReadConfigError,
+};
+use fcsr_pkg::json_edit::JsonDocument;
+use serde_json::Value;
+use std::path::{Component, Path, PathBuf};
+
+pub const EXTENDS_KEY: &str = "extends";
+
+fn read_file(path: &Path) -> Result<Value, ReadConfigError> {
+  let source = std::fs::read_to_string(path)
+    .map_err(|error| ReadConfigError::Io(path.to_path_buf(), error))?;
+  if path
+    .extension()
+    .is_some_and(|extension| extension == "toml")
+  {
+    toml_edit::de::from_str(&source)
+      .map_err(|error| ReadConfigError::Toml(path.to_path_buf(), error))
+  } else {
+    JsonDocument::parse(&source)
+      .map(|document| document.to_value())
+      .map_err(|error| ReadConfigError::Jsonc(path.to_path_buf(), error))
+  }
+}
+
+fn config_in(dir: &Path) -> Option<PathBuf> {
+  let main = std::fs::read_to_string(dir.join("package.json"))
+    .ok()
+    .and_then(|source| serde_json::from_str::<Value>(&source).ok())
+    .and_then(|package_json| package_json["main"].as_str().map(|main| dir.join(main)))
+    .filter(|main| {
+      main
+        .extension()
+        .is_some_and(|extension| ["json", "jsonc", "toml"].contains(&&*extension.to_string_lossy()))
+    });
+  main
+    .into_iter()
+    .chain([dir.join("config.json")])
+    .find(|path| path.is_file())
+}
+
+/// Resolves a relative path against the extending file, or a preset name through `node_modules`.
+fn locate(extends: &str, file: &Path) -> Option<PathBuf> {
+  let dir = file.parent().unwrap_or(Path::new("."));
+  let candidates: Vec<PathBuf> = if extends.starts_with('.') || Path::new(extends).is_absolute() {
+    let path = dir.join(extends);
+    vec![path
+      .components()
+      .filter(|component| *component != Component::CurDir)
+      .collect()]
+  } else {
+    dir
+      .ancestors()
+      .map(|dir| dir.join("node_modules").join(extends))
+      .collect()
+  };
+  candidates.into_iter().find_map(|candidate| {
+    if candidate.is_file() {
+      Some(candidate)
+    } else if candidate.is_dir() {
+      config_in(&candidate)
+    } else {
+      None
+    }
+  })
+}
+
+/// Follows `extends` from `file`, returning every config from the furthest base to `file` itself.
+pub fn extends_chain(file: &Path, value: Value) -> Result<Vec<(PathBuf, Value)>, ReadConfigError> {
+  let mut chain = vec![(file.to_path_buf(), value)];
+  let mut seen = vec![file.canonicalize().unwrap_or(file.to_path_buf())];
+  loop {
+    let (file, value) = chain.last().unwrap_or_else(|| unreachable!());
+    let Some(extends) = value.get(EXTENDS_KEY).and_then(Value::as_str) else {
+      break;
+    };
+    let path = locate(extends, file)
+      .ok_or_else(|| ReadConfigError::ExtendsNotFound(extends.to_string(), file.clone()))?;
+    let canonical = path.canonicalize().unwrap_or(path.clone());
+    if let Some(start) = seen.iter().position(|seen| *seen == canonical) {
+      let mut cycle: Vec<_> = chain[start..]
+        .iter()
+        .map(|(file, _)| file.clone())
+        .collect();
+      cycle.push(path);
+      return Err(ReadConfigError::ExtendsCycle(cycle));
+    }
+
+    let value = read_file(&path)?;
+    let errors = validate(&config_schema(), &value);
+    if !errors.is_empty() {
+      return Err(ReadConfigError::Schema(path, errors));
+    }
+    seen.push(canonical);
+    chain.push((path, value));
+  }
+  chain.reverse();
+  Ok(chain)
+}
+
+/// Objects are merged key by key, anything else (including arrays) replaces the base value.
+pub fn merge(base: &mut Value, overlay: Value) {
+  match (base, overlay) {
+    (Value::Object(base), Value::Object(overlay)) => {
+      for (key, value) in overlay {
+        match base.get_mut(&key) {
+          Some(base) => merge(base, value),
+          None => {
+            base.insert(key, value);
+          }
+        }
+      }
+    }
+    (base, overlay) => *base = overlay,
+  }
+}
+
+pub fn merge_layers(layers: impl IntoIterator<Item = Value>) -> Value {
+  let mut merged = Value::Object(Default::default());
+  for value in layers {
+    merge(&mut merged, value);
+  }
+  if let Value::Object(merged) = &mut merged {
+    merged.remove(EXTENDS_KEY);
+  }
+  merged
+}
+
+#[cfg(test)]
+mod test {
+  use crate::written::WrittenConfig;
+  use fcsr_test_utils::write;
+
+  #[test]
+  fn merges_extended_configs() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(
+      root,
+      "node_modules/@acme/release-policy/package.json",
+      r#"{"name":"@acme/release-policy","main":"policy.jsonc"}"#,
+    );
+    write(
+      root,
+      "node_modules/@acme/release-policy/policy.jsonc",
+      r#"{
+        // shared by every repo
+        "access": "public",
+        "ignore": ["docs"],
+        "snapshot": {"useCalculatedVersion": true, "prereleaseTemplate": "{tag}"},
+      }"#,
+    );
+    write(
+      root,
+      "config/base.toml",
+      "extends = \"@acme/release-policy\"\nbaseBranch = \"main\"\n[snapshot]\nprereleaseTemplate = \"{commit}\"\n",
+    );
+    write(
+      root,
+      ".changeset/config.json",
+      r#"{"extends":"../config/base.toml","ignore":["examples"]}"#,
+    );
+
+    let config = WrittenConfig::read(root).unwrap();
+    assert_eq!(
+      serde_json::to_value(config).unwrap(),
+      serde_json::json!({
+        "access": "public",
+        "baseBranch": "main",
+        "ignore": ["examples"],
+        "snapshot": {"useCalculatedVersion": true, "prereleaseTemplate": "{commit}"}
+      })
+    );
+  }
+
+  #[test]
+  fn reports_cycles_and_missing_configs() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(root, ".changeset/config.json", r#"{"extends":"./a.json"}"#);
+    write(root, ".changeset/a.json", r#"{"extends":"./b.json"}"#);
+    write(root, ".changeset/b.json", r#"{"extends":"./a.json"}"#);
+    assert_eq!(
+      WrittenConfig::read(root).unwrap_err().to_string(),
+      format!(
+        "Config files extend each other in a cycle: {} -> {} -> {}",
+        root.join(".changeset/a.json").display(),
+        root.join(".changeset/b.json").display(),
+        root.join(".changeset/a.json").display(),
+      )
+    );
+
+    write(root, ".changeset/b.json", r#"{"extends":"missing-preset"}"#);
+    assert_eq!(
+      WrittenConfig::read(root).unwrap_err().to_string(),
+      format!(
+        "Could not find the config \"missing-preset\" extended by {}",
+        root.join(".changeset/b.json").display()
+      )
+    );
+  }
+}
@@ -0,0 +1,708 @@
+use crate::{
+  schema::{config_schema, validate},
+  written::WrittenConfig,
+};
+use fcsr_diagnostic::{Diagnostic, Diagnostics};
+use serde_json::{json, Map, Value};
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+pub const LEGACY_CONFIG_FILE: &str = "config.js";
+
+const HOOKS: [&str; 2] = ["getReleaseLine", "getDependencyReleaseLine"];
+
+#[derive(Debug, Error)]
+pub enum LegacyConfigError {
+  #[error("Failed to read {0}: {1}")]
+  Io(PathBuf, std::io::Error),
+  #[error("Failed to parse {0}: unexpected {1} at byte {2}")]
+  Parse(PathBuf, String, usize),
+  #[error("{0} does not assign an object literal to `module.exports`")]
+  NoExports(PathBuf),
+  #[error("Failed to translate {0}: {1}")]
+  Json(PathBuf, serde_json::Error),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum JsValue {
+  Scalar(Value),
+  Undefined,
+  Array(Vec<JsValue>),
+  Object(Vec<(String, JsValue)>),
+  Require(String),
+  Unsupported(String),
+}
+
+impl JsValue {
+  fn get(&self, key: &str) -> Option<&JsValue> {
+    match self {
+      Self::Object(members) => members
+        .iter()
+        .rev()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value),
+      _ => None,
+    }
+  }
+
+  fn to_json(&self) -> Result<Value, String> {
+    match self {
+      Self::Scalar(value) => Ok(value.clone()),
+      Self::Undefined => Err(String::from("undefined")),
+      Self::Array(items) => items.iter().map(Self::to_json).collect(),
+      Self::Object(members) => members
+        .iter()
+        .filter(|(_, value)| *value != Self::Undefined)
+        .map(|(key, value)| Ok((key.clone(), value.to_json()?)))
+        .collect::<Result<Map<_, _>, _>>()
+        .map(Value::Object),
+      Self::Require(module) => Err(format!("require({})", Value::from(module.as_str()))),
+      Self::Unsupported(source) => Err(source.clone()),
+    }
+  }
+}
+
+struct Parser<'a> {
+  source: &'a str,
+  pos: usize,
+  bindings: HashMap<String, JsValue>,
+}
+
+type ParseResult<T> = Result<T, (String, usize)>;
+
+impl<'a> Parser<'a> {
+  fn peek(&self) -> Option<u8> {
+    self.source.as_bytes().get(self.pos).copied()
+  }
+
+  fn error(&self) -> (String, usize) {
+    match self.source[self.pos..].chars().next() {
+      Some(char) => (format!("'{char}'"), self.pos),
+      None => (String::from("end of input"), self.pos),
+    }
+  }
+
+  /// Skips whitespace and comments, returning whether a newline was crossed.
+  fn skip_trivia(&mut self) -> bool {
+    let start = self.pos;
+    loop {
+      let rest = &self.source[self.pos..];
+      let trimmed = rest.trim_start();
+      self.pos += rest.len() - trimmed.len();
+      if trimmed.starts_with("//") {
+        self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+      } else if trimmed.starts_with("/*") {
+        self.pos += trimmed.find("*/").map_or(trimmed.len(), |end| end + 2);
+      } else {
+        break;
+      }
+    }
+    self.source[start..self.pos].contains('\n')
+  }
+
+  fn eat(&mut self, token: &str) -> bool {
+    self.skip_trivia();
+    let rest = &self.source[self.pos..];
+    if !rest.starts_with(token) {
+      return false;
+    }
+    let boundary = rest
+      .get(token.len()..)
+      .and_then(|after| after.chars().next())
+      .is_none_or(|char| !is_ident_char(char) || !token.ends_with(is_ident_char));
+    if boundary {
+      self.pos += token.len();
+      true
+    } else {
+      false
+    }
+  }
+
+  fn expect(&mut self, token: &str) -> ParseResult<()> {
+    if self.eat(token) {
+      Ok(())
+    } else {
+      Err(self.error())
+    }
+  }
+
+  fn ident(&mut self) -> Option<&'a str> {
+    self.skip_trivia();
+    let rest = &self.source[self.pos..];
+    if !rest.starts_with(|char: char| char.is_ascii_alphabetic() || char == '_' || char == '$') {
+      return None;
+    }
+    let len = rest.find(|char| !is_ident_char(char)).unwrap_or(rest.len());
+    self.pos += len;
+    Some(&rest[..len])
+  }
+
+  fn string(&mut self) -> ParseResult<Option<String>> {
+    let quote = self.source.as_bytes()[self.pos];
+    let start = self.pos;
+    let mut value = String::new();
+    let mut chars = self.source[start + 1..].char_indices();
+    while let Some((index, char)) = chars.next() {
+      match char {
+        '\\' => match chars.next() {
+          Some((_, 'n')) => value.push('\n'),
+          Some((_, 't')) => value.push('\t'),
+          Some((_, 'r')) => value.push('\r'),
+          Some((_, escaped)) => value.push(escaped),
+          None => break,
+        },
+        '$' if quote == b'`' && self.source[start + 2 + index..].starts_with('{') => {
+          return Ok(None)
+        }
+        char if char as u32 == quote as u32 => {
+          self.pos = start + 2 + index;
+          return Ok(Some(value));
+        }
+        char => value.push(char),
+      }
+    }
+    self.pos = self.source.len();
+    Err(self.error())
+  }
+
+  fn primary(&mut self) -> ParseResult<JsValue> {
+    self.skip_trivia();
+    let value = match self.peek() {
+      Some(b'{') => {
+        self.pos += 1;
+        let mut members = vec![];
+        while !self.eat("}") {
+          members.push(self.member()?);
+          if !self.eat(",") {
+            self.expect("}")?;
+            break;
+          }
+        }
+        JsValue::Object(members)
+      }
+      Some(b'[') => {
+        self.pos += 1;
+        let mut items = vec![];
+        while !self.eat("]") {
+          items.push(self.expression(false)?);
+          if !self.eat(",") {
+            self.expect("]")?;
+            break;
+          }
+        }
+        JsValue::Array(items)
+      }
+      Some(b'"' | b'\'' | b'`') => match self.string()? {
+        Some(value) => JsValue::Scalar(Value::String(value)),
+        None => return Err(self.error()),
+      },
+      Some(byte) if byte.is_ascii_digit() || byte == b'-' || byte == b'.' => {
+        let start = self.pos;
+        self.pos += 1;
+        while matches!(self.peek(), Some(byte) if byte.is_ascii_alphanumeric() || byte == b'.') {
+          self.pos += 1;
+        }
+        let number = self.source[start..self.pos].trim_start_matches('+');
+        JsValue::Scalar(serde_json::from_str(number).map_err(|_| self.error())?)
+      }
+      _ => match self.ident() {
+        Some("true") => JsValue::Scalar(Value::Bool(true)),
+        Some("false") => JsValue::Scalar(Value::Bool(false)),
+        Some("null") => JsValue::Scalar(Value::Null),
+        Some("undefined") => JsValue::Undefined,
+        Some("require") => {
+          self.expect("(")?;
+          self.skip_trivia();
+          if !matches!(self.peek(), Some(b'"' | b'\'' | b'`')) {
+            return Err(self.error());
+          }
+          let module = self.string()?.ok_or_else(|| self.error())?;
+          self.expect(")")?;
+          JsValue::Require(module)
+        }
+        Some(name) => self
+          .bindings
+          .get(name)
+          .cloned()
+          .ok_or_else(|| self.error())?,
+        None => return Err(self.error()),
+      },
+    };
+
+    let mut value = value;
+    while self.eat(".") {
+      let key = self.ident().ok_or_else(|| self.error())?;
+      value = match value {
+        JsValue::Require(module) => JsValue::Require(module),
+        value @ JsValue::Object(_) => value.get(key).cloned().unwrap_or(JsValue::Undefined),
+        _ => return Err(self.error()),
+      };
+    }
+    Ok(value)
+  }
+
+  fn member(&mut self) -> ParseResult<(String, JsValue)> {
+    self.skip_trivia();
+    let start = self.pos;
+    let key = match self.peek() {
+      Some(b'"' | b'\'') => self.string()?.ok_or_else(|| self.error())?,
+      _ => match self.ident() {
+        Some(key) => key.to_string(),
+        None => {
+          self.skip_expression(start, false);
+          return Ok((String::new(), self.unsupported(start)));
+        }
+      },
+    };
+    if self.eat(":") {
+      return Ok((key, self.expression(false)?));
+    }
+    self.skip_trivia();
+    match self.peek() {
+      Some(b',' | b'}') => {
+        let value = self
+          .bindings
+          .get(&key)
+          .cloned()
+          .unwrap_or_else(|| JsValue::Unsupported(key.clone()));
+        Ok((key, value))
+      }
+      _ => {
+        self.skip_expression(start, false);
+        Ok((key, self.unsupported(start)))
+      }
+    }
+  }
+
+  fn expression(&mut self, statement: bool) -> ParseResult<JsValue> {
+    self.skip_trivia();
+    let start = self.pos;
+    if let Ok(value) = self.primary() {
+      let newline = self.skip_trivia();
+      if matches!(self.peek(), None | Some(b',' | b'}' | b']' | b')' | b';'))
+        || (statement && newline)
+      {
+        return Ok(value);
+      }
+    }
+    if !self.skip_expression(start, statement) || self.pos == start {
+      return Err(self.error());
+    }
+    Ok(self.unsupported(start))
+  }
+
+  fn unsupported(&self, start: usize) -> JsValue {
+    JsValue::Unsupported(self.source[start..self.pos].trim().to_string())
+  }
+
+  /// Moves to the end of the expression or statement starting at `start`, balancing brackets.
+  /// Returns false when the input ends inside an unclosed bracket.
+  fn skip_expression(&mut self, start: usize, statement: bool) -> bool {
+    self.pos = start;
+    let mut depth = 0usize;
+    while let Some(byte) = self.peek() {
+      match byte {
+        b'"' | b'\'' | b'`' => {
+          let rest = &self.source.as_bytes()[self.pos + 1..];
+          let mut escaped = false;
+          let end = rest.iter().position(|char| {
+            let end = !escaped && *char == byte;
+            escaped = !escaped && *char == b'\\';
+            end
+          });
+          self.pos += end.map_or(rest.len() + 1, |end| end + 2);
+          continue;
+        }
+        b'/' if matches!(self.source.as_bytes().get(self.pos + 1), Some(b'/' | b'*')) => {
+          if self.skip_trivia() && statement && depth == 0 {
+            return true;
+          }
+          continue;
+        }
+        b'(' | b'[' | b'{' => depth += 1,
+        b')' | b']' | b'}' if depth == 0 => return true,
+        b')' | b']' | b'}' => depth -= 1,
+        b',' | b';' if depth == 0 => return true,
+        b'\n' if statement && depth == 0 => return true,
+        _ => {}
+      }
+      self.pos += 1;
+    }
+    depth == 0
+  }
+
+  fn program(&mut self) -> ParseResult<Option<JsValue>> {
+    let mut exports = None;
+    loop {
+      self.skip_trivia();
+      let start = self.pos;
+      if self.peek().is_none() {
+        return Ok(exports);
+      }
+      let declaration = (self.eat("const") || self.eat("let") || self.eat("var"))
+        .then(|| self.ident())
+        .flatten()
+        .filter(|_| self.eat("="));
+      if let Some(name) = declaration {
+        let value = self.expression(true)?;
+        self.bindings.insert(name.to_string(), value);
+      } else if self.eat("module.exports") {
+        self.expect("=")?;
+        exports = Some(self.expression(true)?);
+      } else if self.eat("export default") {
+        exports = Some(self.expression(true)?);
+      } else {
+        self.skip_expression(start, true);
+      }
+      if !self.eat(";") && self.pos == start {
+        self.pos += 1;
+      }
+    }
+  }
+}
+
+fn is_ident_char(char: char) -> bool {
+  char.is_ascii_alphanumeric() || char == '_' || char == '$'
+}
+
+fn first_line(source: &str) -> String {
+  match source.split_once('\n') {
+    Some((line, _)) => format!("{} ...", line.trim_end()),
+    None => source.to_string(),
+  }
+}
+
+struct Translator<'a> {
+  file: &'a Path,
+  config: Map<String, Value>,
+  diagnostics: Diagnostics,
+}
+
+impl Translator<'_> {
+  fn untranslatable(&mut self, path: &str, value: &JsValue, help: Option<&str>) {
+    let source = match value.to_json() {
+      Ok(value) => value.to_string(),
+      Err(source) => source,
+    };
+    let diagnostic = Diagnostic::warning(
+      "migrate/untranslatable",
+      format!("`{path}` could not be translated: {}", first_line(&source)),
+    )
+    .with_file(self.file);
+    self.diagnostics.push(match help {
+      Some(help) => diagnostic.with_help(help),
+      None => diagnostic,
+    });
+  }
+
+  fn set(&mut self, key: &str, path: &str, value: &JsValue) {
+    match value.to_json() {
+      Ok(json) => {
+        self.config.insert(key.to_string(), json);
+      }
+      Err(_) => self.untranslatable(path, value, None),
+    }
+  }
+
+  fn bool(&mut self, path: &str, value: &JsValue) -> Option<bool> {
+    match value {
+      JsValue::Scalar(Value::Bool(value)) => Some(*value),
+      JsValue::Undefined => None,
+      value => {
+        self.untranslatable(path, value, None);
+        None
+      }
+    }
+  }
+
+  fn hook(&mut self, path: &str, value: &JsValue, modules: &mut Vec<String>) {
+    match value {
+      JsValue::Require(module) | JsValue::Scalar(Value::String(module)) => {
+        modules.push(module.clone())
+      }
+      JsValue::Undefined => {}
+      value => self.untranslatable(
+        path,
+        value,
+        Some("v2 reads both release line functions from one changelog module. Move them into a module that exports `getReleaseLine` and `getDependencyReleaseLine` and set `changelog` to its path"),
+      ),
+    }
+  }
+
+  fn translate(&mut self, exports: &[(String, JsValue)]) {
+    let known: Vec<String> = config_schema()["properties"]
+      .as_object()
+      .map(|properties| properties.keys().cloned().collect())
+      .unwrap_or_default();
+    let mut commits = vec![];
+    let mut skip_ci = None;
+    let mut update_changelog = None;
+    let mut modules = vec![];
+
+    for (key, value) in exports {
+      match key.as_str() {
+        "commit" => commits.extend(self.bool(key, value).map(|commit| (key.clone(), commit))),
+        "changesetOptions" | "versionOptions" | "publishOptions" => {
+          let JsValue::Object(options) = value else {
+            self.untranslatable(key, value, None);
+            continue;
+          };
+          for (option, value) in options {
+            let path = format!("{key}.{option}");
+            match (key.as_str(), option.as_str()) {
+              ("changesetOptions" | "versionOptions", "commit") => {
+                commits.extend(self.bool(&path, value).map(|commit| (path.clone(), commit)))
+              }
+              ("versionOptions", "skipCI") => skip_ci = self.bool(&path, value),
+              ("versionOptions", "updateChangelog") => update_changelog = self.bool(&path, value),
+              ("versionOptions", hook) if HOOKS.contains(&hook) => {
+                self.hook(&path, value, &mut modules)
+              }
+              ("publishOptions", "public") => {
+                if let Some(public) = self.bool(&path, value) {
+                  let access = if public { "public" } else { "restricted" };
+                  self.config.insert(String::from("access"), json!(access));
+                }
+              }
+              _ => self.untranslatable(&path, value, None),
+            }
+          }
+        }
+        hook if HOOKS.contains(&hook) => self.hook(key, value, &mut modules),
+        key if known.iter().any(|known| known == key) && key != "$schema" => {
+          self.set(key, key, value)
+        }
+        _ => self.untranslatable(key, value, None),
+      }
+    }
+
+    if commits.iter().any(|(_, commit)| *commit) {
+      if commits.iter().any(|(_, commit)| !*commit) {
+        let paths: Vec<_> = commits
+          .iter()
+          .map(|(path, _)| format!("`{path}`"))
+          .collect();
+        self.diagnostics.push(
+          Diagnostic::warning(
+            "migrate/commit",
+            format!(
+              "{} disagree, v2 uses a single `commit` option for every command so it was set to true",
+              paths.join(" and ")
+            ),
+          )
+          .with_file(self.file),
+        );
+      }
+      let commit = match skip_ci {
+        Some(false) => json!(["@changesets/cli/commit", {"skipCI": false}]),
+        _ => json!(true),
+      };
+      self.config.insert(String::from("commit"), commit);
+    } else if !commits.is_empty() {
+      self.config.insert(String::from("commit"), json!(false));
+    }
+
+    modules.dedup();
+    match (update_changelog, modules.as_slice()) {
+      (Some(false), _) => {
+        self.config.insert(String::from("changelog"), json!(false));
+      }
+      (_, [module]) => {
+        self.config.insert(String::from("changelog"), json!(module));
+      }
+      (_, []) => {}
+      (_, modules) => self.diagnostics.push(
+        Diagnostic::warning(
+          "migrate/untranslatable",
+          format!(
+            "The release line hooks come from different modules ({}) and could not be combined into one `changelog` option",
+            modules.join(", ")
+          ),
+        )
+        .with_file(self.file),
+      ),
+    }
+  }
+}
+
+pub fn translate_legacy_config(
+  source: &str,
+  file: &Path,
+) -> Result<(WrittenConfig, Diagnostics), LegacyConfigError> {
+  let mut parser = Parser {
+    source,
+    pos: 0,
+    bindings: HashMap::new(),
+  };
+  let exports = parser
+    .program()
+    .map_err(|(token, pos)| LegacyConfigError::Parse(file.to_path_buf(), token, pos))?;
+  let Some(JsValue::Object(exports)) = exports else {
+    return Err(LegacyConfigError::NoExports(file.to_path_buf()));
+  };
+
+  let mut translator = Translator {
+    file,
+    config: Map::new(),
+    diagnostics: Diagnostics::default(),
+  };
+  translator.translate(&exports);
+  let Translator {
+    mut config,
+    mut diagnostics,
+    ..
+  } = translator;
+
+  for error in validate(&config_schema(), &Value::Object(config.clone())) {
+    let key = error.path.split(['.', '[']).next().unwrap_or_default();
+    config.remove(key);
+    diagnostics.push(
+      Diagnostic::warning("migrate/invalid", format!("Dropped invalid option {error}"))
+        .with_file(file),
+    );
+  }
+
+  let defaults = WrittenConfig {
+    base_branch: None,
+    ..WrittenConfig::default()
+  };
+  let mut value = serde_json::to_value(defaults)
+    .map_err(|error| LegacyConfigError::Json(file.to_path_buf(), error))?;
+  if let Value::Object(value) = &mut value {
+    value.extend(config);
+  }
+  let config = serde_json::from_value(value)
+    .map_err(|error| LegacyConfigError::Json(file.to_path_buf(), error))?;
+  Ok((config, diagnostics))
+}
+
+pub fn read_legacy_config(file: &Path) -> Result<(WrittenConfig, Diagnostics), LegacyConfigError> {
+  let source = std::fs::read_to_string(file)
+    .map_err(|error| LegacyConfigError::Io(file.to_path_buf(), error))?;
+  translate_legacy_config(&source, file)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn translate(source: &str) -> (Value, Vec<String>) {
+    let (config, diagnostics) = translate_legacy_config(source, Path::new("config.js")).unwrap();
+    (
+      serde_json::to_value(config).unwrap(),
+      diagnostics
+        .iter()
+        .map(|diagnostic| format!("{}: {}", diagnostic.code, diagnostic.message))
+        .collect(),
+    )
+  }
+
+  #[test]
+  fn translates_v1_options() {
+    let (config, diagnostics) = translate(
+      r#"
+"use strict";
+// the defaults written by @changesets/cli v1
+const changesetOptions = {
+  commit: true,
+};
+
+const versionOptions = {
+  commit: true,
+  skipCI: false,
+  updateChangelog: true,
+  getReleaseLine: require('@changesets/changelog-github').getReleaseLine,
+  getDependencyReleaseLine: require("@changesets/changelog-github"),
+};
+
+module.exports = {
+  changesetOptions,
+  versionOptions,
+  publishOptions: { public: true },
+  linked: [["pkg-a", "pkg-b"],],
+};
+"#,
+    );
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    assert_eq!(
+      config,
+      json!({
+        "$schema": "./schema.json",
+        "changelog": "@changesets/changelog-github",
+        "commit": ["@changesets/cli/commit", {"skipCI": false}],
+        "fixed": [],
+        "linked": [["pkg-a", "pkg-b"]],
+        "access": "public",
+        "updateInternalDependencies": "patch",
+        "ignore": []
+      })
+    );
+  }
+
+  #[test]
+  fn reports_untranslatable_options() {
+    let (config, diagnostics) = translate(
+      r#"
+const getReleaseLine = async (changeset, type) => {
+  return `- ${changeset.summary}`;
+};
+
+module.exports = {
+  changesetOptions: { commit: false },
+  versionOptions: { commit: true, updateChangelog: false, getReleaseLine },
+  access: "everyone",
+  ignore: process.env.IGNORE.split(","),
+  prettier: false,
+}
+"#,
+    );
+    assert_eq!(config["commit"], json!(true));
+    assert_eq!(config["changelog"], json!(false));
+    assert_eq!(config["access"], json!("restricted"));
+    assert_eq!(
+      diagnostics,
+      vec![
+        "migrate/untranslatable: `versionOptions.getReleaseLine` could not be translated: async (changeset, type) => { ...",
+        "migrate/untranslatable: `ignore` could not be translated: process.env.IGNORE.split(\",\")",
+        "migrate/untranslatable: `prettier` could not be translated: false",
+        "migrate/commit: `changesetOptions.commit` and `versionOptions.commit` disagree, v2 uses a single `commit` option for every command so it was set to true",
+        "migrate/invalid: Dropped invalid option `access` should be one of \"public\", \"restricted\", \"private\", found \"everyone\"",
+      ]
+    );
+  }
+
+  #[test]
+  fn rejects_configs_without_exports() {
+    assert!(matches!(
+      translate_legacy_config("const a = {};", Path::new("config.js")),
+      Err(LegacyConfigError::NoExports(_))
+    ));
+    assert!(matches!(
+      translate_legacy_config("module.exports = {", Path::new("config.js")),
+      Err(LegacyConfigError::Parse(..))
+    ));
+  }
+
+  #[test]
+  fn handles_non_ascii_input() {
+    let (_, diagnostics) = translate("module.exports = { é: 1 }");
+    assert_eq!(
+      diagnostics,
+      vec!["migrate/untranslatable: `` could not be translated: é: 1"]
+    );
+    let (_, diagnostics) = translate("module.exports = { a: 1é }");
+    assert_eq!(
+      diagnostics,
+      vec!["migrate/untranslatable: `a` could not be translated: 1é"]
+    );
+    let (config, _) = translate(r#"module.exports = { baseBranch: "café" } // ünïcode"#);
+    assert_eq!(config["baseBranch"], "café");
+    assert!(matches!(
+      translate_legacy_config(r#"module.exports = { a: "é"#, Path::new("config.js")),
+      Err(LegacyConfigError::Parse(..))
+    ));
+  }
+}
@@ -1,114 +1,498 @@
-mod config;
-mod dependency_graph;
-mod written;
+pub mod config;
+pub mod dependency_graph;
+pub mod edit;
+pub mod env;
+pub mod extends;
+pub mod legacy;
+pub mod schema;
+pub mod source;
+pub mod written;
 
+use config::{
+  Config, ConfigContainer, DependencyLint, ExperimentalOptions, Lint, PackageSources,
+  PrivatePackages, Snapshot, TagPackage,
+};
 use dependency_graph::get_dependents_graph;
-use fcsr_pkg::access_type::AccessType;
+use fcsr_diagnostic::{Diagnostic, Diagnostics};
+use fcsr_pkg::{access_type::AccessType, packages::Packages};
 use glob::Pattern;
+use schema::SchemaError;
 use serde::Serialize;
-use std::collections::HashSet;
+use std::{collections::HashSet, fmt::Display, path::Path};
+use thiserror::Error;
+use written::{
+  ConfigContainerTuple, PrivateChangesets, PrivatePackagesEnum, ReadConfigError,
+  UpdateInternalDependencies, UpdateInternalDependents, WrittenConfig,
+};
 
 pub type PackageGroup = Vec<String>;
 
-pub fn parse(json: written::WrittenConfig, packages: fcsr_pkg::packages::Packages) {
-  let mut pkg_names: Vec<_> = packages
+#[derive(Debug, Error)]
+pub enum ConfigError {
+  #[error(transparent)]
+  Read(#[from] ReadConfigError),
+  #[error("Invalid config {0}: {1}")]
+  Schema(std::path::PathBuf, SchemaError),
+  #[error("The `{0}` option is set as {1} when the only valid values are false, a module path or a tuple with a module path and options")]
+  InvalidContainer(&'static str, String),
+  #[error("Invalid glob expression \"{0}\" in the `{1}` option: {2}")]
+  InvalidGlob(String, &'static str, glob::PatternError),
+  #[error("The package or glob expression \"{0}\" specified in the `{1}` option does not match any package in the project. You may have misspelled the package name or provided an invalid glob expression. Note that glob expressions must be defined according to https://docs.rs/glob/0.3.1/glob/struct.Pattern.html.")]
+  NoMatch(String, &'static str, Option<String>),
+  #[error("The package \"{0}\" is defined in multiple sets of {1} packages. Packages can only be defined in a single set of {1} packages. If you are using glob expressions, make sure that they are valid according to https://docs.rs/glob/0.3.1/glob/struct.Pattern.html.")]
+  DuplicateInGroups(String, ParsePackageGroupType),
+  #[error("The package \"{0}\" can be found in both fixed and linked groups. A package can only be either fixed or linked.")]
+  FixedAndLinked(String),
+  #[error(r#"The package "{0}" depends on the ignored package "{1}", but "{0}" is not being ignored. Please add "{0}" to the `ignore` option."#)]
+  IgnoredDependency(String, String),
+  #[error(r#"The tag-only package "{0}" is matched by a `tagFormat` override in the `packages` option, but its tags are named by the `tagPrefix` of its `tagPackages` entry."#)]
+  TagPackageTagFormat(String),
+}
+
+impl ConfigError {
+  pub fn to_diagnostic(&self) -> Diagnostic {
+    let glob_help = "Glob expressions must be valid according to https://docs.rs/glob/0.3.1/glob/struct.Pattern.html.";
+    let diagnostic = |code| Diagnostic::error(code, self.to_string());
+    match self {
+      Self::Read(error) => match error.path() {
+        Some(path) => diagnostic("config/read").with_file(path),
+        None => diagnostic("config/read"),
+      },
+      Self::Schema(path, _) => diagnostic("config/schema")
+        .with_file(path)
+        .with_help("Run `fcsr config schema` to see every supported option"),
+      Self::InvalidContainer(..) => diagnostic("config/invalid-container"),
+      Self::InvalidGlob(..) => diagnostic("config/invalid-glob").with_help(glob_help),
+      Self::NoMatch(_, _, Some(suggestion)) => {
+        diagnostic("config/no-match").with_help(format!("Did you mean \"{suggestion}\"?"))
+      }
+      Self::NoMatch(..) => diagnostic("config/no-match"),
+      Self::DuplicateInGroups(package, _) => diagnostic("config/duplicate-in-groups")
+        .with_package(package)
+        .with_help(glob_help),
+      Self::FixedAndLinked(package) => diagnostic("config/fixed-and-linked").with_package(package),
+      Self::IgnoredDependency(dependent, _) => diagnostic("config/ignored-dependency")
+        .with_package(dependent)
+        .with_help(format!("Add \"{dependent}\" to the `ignore` option")),
+      Self::TagPackageTagFormat(package) => diagnostic("config/tag-package-tag-format")
+        .with_package(package)
+        .with_help("Set `tagPrefix` in the `tagPackages` option instead"),
+    }
+  }
+}
+
+#[derive(Debug, Error)]
+pub struct ConfigErrors(pub Vec<ConfigError>);
+
+impl ConfigErrors {
+  pub fn diagnostics(&self) -> Diagnostics {
+    self.0.iter().map(ConfigError::to_diagnostic).collect()
+  }
+}
+
+impl Display for ConfigErrors {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let messages: Vec<_> = self.0.iter().map(|error| error.to_string()).collect();
+    write!(f, "{}", messages.join("\n"))
+  }
+}
+
+impl From<ReadConfigError> for ConfigErrors {
+  fn from(error: ReadConfigError) -> Self {
+    match error {
+      ReadConfigError::Schema(path, errors) => Self(
+        errors
+          .into_iter()
+          .map(|error| ConfigError::Schema(path.clone(), error))
+          .collect(),
+      ),
+      error => Self(vec![ConfigError::Read(error)]),
+    }
+  }
+}
+
+pub fn load_package_sources(root: &Path) -> Result<PackageSources, ConfigErrors> {
+  Ok(package_sources(&WrittenConfig::read(root)?))
+}
+
+fn package_sources(json: &WrittenConfig) -> PackageSources {
+  PackageSources {
+    python_packages: json.python_packages.clone().unwrap_or_default(),
+    tag_packages: json
+      .tag_packages
+      .iter()
+      .flatten()
+      .map(|tag_package| TagPackage {
+        dir: tag_package.dir.clone(),
+        name: tag_package.name(),
+        tag_prefix: tag_package.tag_prefix(),
+      })
+      .collect(),
+  }
+}
+
+pub fn load_config(root: &Path, packages: &Packages) -> Result<Config, ConfigErrors> {
+  parse(WrittenConfig::read(root)?, packages)
+}
+
+fn parse_container(
+  default: ConfigContainer,
+  written: Option<written::ConfigContainer>,
+  option: &'static str,
+  errors: &mut Vec<ConfigError>,
+) -> ConfigContainer {
+  match written {
+    None | Some(written::ConfigContainer::Bool(false)) => ConfigContainer::Bool(false),
+    Some(written::ConfigContainer::Bool(true)) => default,
+    Some(written::ConfigContainer::String(module)) => ConfigContainer::Tuple(module, None),
+    Some(written::ConfigContainer::Tuple(tuple)) => match tuple.as_slice() {
+      [ConfigContainerTuple::String(module)] => ConfigContainer::Tuple(module.clone(), None),
+      [ConfigContainerTuple::String(module), options] => ConfigContainer::Tuple(
+        module.clone(),
+        Some(serde_json::to_value(options).unwrap_or_default()),
+      ),
+      _ => {
+        errors.push(ConfigError::InvalidContainer(
+          option,
+          serde_json::to_string(&tuple).unwrap_or_default(),
+        ));
+        ConfigContainer::Bool(false)
+      }
+    },
+  }
+}
+
+fn parse_ignore<'a>(
+  patterns: impl IntoIterator<Item = &'a String>,
+  pkg_names: &[String],
+  packages: &Packages,
+  option: &'static str,
+  errors: &mut Vec<ConfigError>,
+) -> Vec<String> {
+  let mut ignore = vec![];
+  for pattern in patterns {
+    for pkg_name in match_packages(pattern, pkg_names, option, errors) {
+      if !ignore.contains(&pkg_name) {
+        ignore.push(pkg_name);
+      }
+    }
+  }
+  if !ignore.is_empty() {
+    let dependents_graph = get_dependents_graph(packages, None);
+    for ignored_package in ignore.iter() {
+      for dependent in dependents_graph.get(ignored_package).into_iter().flatten() {
+        if !ignore.contains(dependent) {
+          errors.push(ConfigError::IgnoredDependency(
+            dependent.clone(),
+            ignored_package.clone(),
+          ));
+        }
+      }
+    }
+  }
+  ignore
+}
+
+/// Adds one-off ignore patterns, like the ones given to `fcsr version --ignore`.
+pub fn extend_ignore(
+  config: &mut Config,
+  patterns: &[String],
+  packages: &Packages,
+) -> Result<(), ConfigErrors> {
+  if patterns.is_empty() {
+    return Ok(());
+  }
+  let mut errors = vec![];
+  let pkg_names: Vec<_> = packages
     .packages
     .iter()
     .map(|package| package.package_json.name.clone())
     .collect();
+  let patterns = config.ignore.iter().chain(patterns);
+  config.ignore = parse_ignore(patterns, &pkg_names, packages, "--ignore", &mut errors);
+  if errors.is_empty() {
+    Ok(())
+  } else {
+    Err(ConfigErrors(errors))
+  }
+}
+
+pub fn parse(json: WrittenConfig, packages: &Packages) -> Result<Config, ConfigErrors> {
+  let mut errors = vec![];
+  let pkg_names: Vec<_> = packages
+    .packages
+    .iter()
+    .map(|package| package.package_json.name.clone())
+    .collect();
+
+  let access = match json.access {
+    Some(AccessType::Public) => AccessType::Public,
+    _ => AccessType::Restricted,
+  };
 
-  let normalized_access = match json.access {
-    Some(access) => match access {
-      AccessType::Restricted | AccessType::Private => AccessType::Restricted,
-      AccessType::Public => AccessType::Public,
+  let fixed = parse_package_group(
+    json.fixed.clone().unwrap_or_default(),
+    &pkg_names,
+    ParsePackageGroupType::Fixed,
+    &mut errors,
+  );
+  let linked = parse_package_group(
+    json.linked.clone().unwrap_or_default(),
+    &pkg_names,
+    ParsePackageGroupType::Linked,
+    &mut errors,
+  );
+
+  let all_fixed_packages: HashSet<&String> = fixed.iter().flatten().collect();
+  let all_linked_packages: HashSet<&String> = linked.iter().flatten().collect();
+  for fixed_pkg_name in pkg_names.iter() {
+    if all_fixed_packages.contains(fixed_pkg_name) && all_linked_packages.contains(fixed_pkg_name) {
+      errors.push(ConfigError::FixedAndLinked(fixed_pkg_name.clone()));
+    }
+  }
+
+  let ignore = parse_ignore(
+    json.ignore.iter().flatten(),
+    &pkg_names,
+    packages,
+    "ignore",
+    &mut errors,
+  );
+
+  let private_packages = match json.private_packages {
+    Some(PrivatePackagesEnum::Bool(false)) => PrivatePackages {
+      version: false,
+      tag: false,
+      changesets: PrivateChangesets::Ignore,
+    },
+    Some(PrivatePackagesEnum::PrivatePackages(ref private_packages)) => PrivatePackages {
+      version: private_packages.version.unwrap_or(true),
+      tag: private_packages.tag.unwrap_or(false),
+      changesets: private_packages.changesets.unwrap_or_default(),
+    },
+    _ => PrivatePackages {
+      version: true,
+      tag: false,
+      changesets: PrivateChangesets::Ignore,
     },
-    None => AccessType::Restricted,
   };
 
-  let fixed = if let Some(json_fixed) = json.fixed {
-    parse_package_group(json_fixed, &mut pkg_names, ParsePackageGroupType::Fixed)
-  } else {
-    vec![]
+  let mut overridable = pkg_names.clone();
+  overridable.push(packages.root.package_json.name.clone());
+  for pattern in json.packages.iter().flatten().map(|(pattern, _)| pattern) {
+    if !overridable.contains(pattern) {
+      match_packages(pattern, &overridable, "packages", &mut errors);
+    }
+  }
+
+  let deps_lint = json.lint.as_ref().and_then(|lint| lint.deps.clone());
+  let allow = deps_lint
+    .as_ref()
+    .and_then(|deps| deps.allow.clone())
+    .unwrap_or_default();
+  for pattern in allow.iter() {
+    if let Err(error) = Pattern::new(pattern) {
+      errors.push(ConfigError::InvalidGlob(
+        pattern.clone(),
+        "lint.deps.allow",
+        error,
+      ));
+    }
+  }
+
+  let experimental = json
+    .experimental_unsafe_options_will_change_in_path
+    .as_ref();
+  let snapshot = json.snapshot.as_ref();
+  let package_sources = package_sources(&json);
+  let config = Config {
+    changelog: parse_container(
+      ConfigContainer::Tuple(String::from("@changesets/cli/changelog"), None),
+      json
+        .changelog
+        .clone()
+        .or(Some(written::ConfigContainer::Bool(true))),
+      "changelog",
+      &mut errors,
+    ),
+    commit: parse_container(
+      ConfigContainer::Tuple(
+        String::from("@changesets/cli/commit"),
+        Some(serde_json::json!({ "skipCI": "version" })),
+      ),
+      json.commit.clone(),
+      "commit",
+      &mut errors,
+    ),
+    fixed,
+    linked,
+    access,
+    base_branch: json
+      .base_branch
+      .clone()
+      .unwrap_or_else(|| String::from("master")),
+    changed_file_patterns: json
+      .changed_file_patterns
+      .clone()
+      .unwrap_or_else(|| vec![String::from("**")]),
+    private_packages,
+    update_internal_dependencies: json
+      .update_internal_dependencies
+      .unwrap_or(UpdateInternalDependencies::Patch),
+    ignore,
+    bump_versions_with_workspace_protocol_only: json
+      .bump_versions_with_workspace_protocol_only
+      .unwrap_or(false),
+    experimental_unsafe_options: ExperimentalOptions {
+      only_update_peer_dependents_when_out_of_range: experimental
+        .and_then(|options| options.only_update_peer_dependents_when_out_of_range)
+        .unwrap_or(false),
+      update_internal_dependents: experimental
+        .and_then(|options| options.update_internal_dependents)
+        .unwrap_or(UpdateInternalDependents::OutOfRange),
+    },
+    snapshot: Snapshot {
+      use_calculated_version: snapshot
+        .and_then(|snapshot| snapshot.use_calculated_version)
+        .or_else(|| experimental.and_then(|options| options.use_calculated_version_for_snapshots))
+        .unwrap_or(false),
+      prerelease_template: snapshot.and_then(|snapshot| snapshot.prerelease_template.clone()),
+    },
+    package_sources,
+    packages: json.packages.clone().unwrap_or_default(),
+    lint: Lint {
+      deps: DependencyLint {
+        pin: deps_lint.and_then(|deps| deps.pin).unwrap_or_default(),
+        allow,
+      },
+    },
   };
 
-  let linked = if let Some(json_linked) = json.linked {
-    parse_package_group(json_linked, &mut pkg_names, ParsePackageGroupType::Linked)
+  for tag_package in config.package_sources.tag_packages.iter() {
+    if config
+      .package_options(&tag_package.name)
+      .tag_format
+      .is_some()
+    {
+      errors.push(ConfigError::TagPackageTagFormat(tag_package.name.clone()));
+    }
+  }
+
+  if errors.is_empty() {
+    Ok(config)
   } else {
-    vec![]
-  };
+    Err(ConfigErrors(errors))
+  }
+}
 
-  let all_fixed_packages: HashSet<String> = HashSet::from_iter(fixed.clone().into_iter().flatten());
-  let all_linked_packages: HashSet<String> =
-    HashSet::from_iter(linked.clone().into_iter().flatten());
+#[derive(Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum ParsePackageGroupType {
+  Fixed,
+  Linked,
+}
 
-  for fixed_pkg_name in all_fixed_packages {
-    if all_linked_packages.contains(&fixed_pkg_name) {
-      println!("The package \"{fixed_pkg_name}\" can be found in both fixed and linked groups. A package can only be either fixed or linked.")
+impl Display for ParsePackageGroupType {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Fixed => write!(f, "fixed"),
+      Self::Linked => write!(f, "linked"),
     }
   }
+}
 
-  if let Some(json_ignore) = json.ignore {
-    let dependents_graph = get_dependents_graph(packages, None);
-    for ignored_package in json_ignore.iter() {
-      if let Some(dependents) = dependents_graph.get(ignored_package) {
-        for dependent in dependents {
-          if !json_ignore.contains(dependent) {
-            println!(
-              r#"The package "{dependent}" depends on the ignored package "{ignored_package}", but "{dependent}" is not being ignored. Please add "{dependent}" to the `ignore` option."#
-            );
-          }
-        }
+fn match_packages(
+  pattern: &str,
+  pkg_names: &[String],
+  option: &'static str,
+  errors: &mut Vec<ConfigError>,
+) -> Vec<String> {
+  match Pattern::new(pattern) {
+    Ok(glob) => {
+      let matched: Vec<String> = pkg_names
+        .iter()
+        .filter(|pkg_name| glob.matches(pkg_name))
+        .cloned()
+        .collect();
+      if matched.is_empty() {
+        errors.push(ConfigError::NoMatch(
+          pattern.to_string(),
+          option,
+          suggest(pattern, pkg_names),
+        ));
       }
+      matched
+    }
+    Err(error) => {
+      errors.push(ConfigError::InvalidGlob(pattern.to_string(), option, error));
+      vec![]
     }
   }
 }
 
-#[derive(Serialize, Debug)]
-enum ParsePackageGroupType {
-  Fixed,
-  Linked,
+fn edit_distance(a: &str, b: &str) -> usize {
+  let b: Vec<char> = b.chars().collect();
+  let mut row: Vec<usize> = (0..=b.len()).collect();
+  for (i, a_char) in a.chars().enumerate() {
+    let mut diagonal = row[0];
+    row[0] = i + 1;
+    for (j, b_char) in b.iter().enumerate() {
+      let substitution = diagonal + usize::from(a_char != *b_char);
+      diagonal = row[j + 1];
+      row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+    }
+  }
+  row[b.len()]
+}
+
+fn suggest(pattern: &str, pkg_names: &[String]) -> Option<String> {
+  let max_distance = (pattern.chars().count() / 3).max(2);
+  pkg_names
+    .iter()
+    .map(|pkg_name| (edit_distance(pattern, pkg_name), pkg_name))
+    .filter(|(distance, _)| *distance <= max_distance)
+    .min_by_key(|(distance, _)| *distance)
+    .map(|(_, pkg_name)| pkg_name.clone())
 }
 
 fn parse_package_group(
   group: Vec<PackageGroup>,
-  pkg_names: &mut Vec<String>,
+  pkg_names: &[String],
   r#type: ParsePackageGroupType,
+  errors: &mut Vec<ConfigError>,
 ) -> Vec<PackageGroup> {
+  let option = match r#type {
+    ParsePackageGroupType::Fixed => "fixed",
+    ParsePackageGroupType::Linked => "linked",
+  };
   let mut fixed: Vec<Vec<String>> = vec![];
 
   let mut found_pkg_names = HashSet::<String>::new();
-  let mut duplicated_pkg_names = HashSet::<String>::new();
+  let mut duplicated_pkg_names = vec![];
 
   for fixed_group in group {
     let mut expanded_fixed_group = vec![];
     for fixed_group_item in fixed_group {
-      if let Ok(ref pattern) = Pattern::new(&fixed_group_item) {
-        pkg_names.iter().for_each(|pkg_name| {
-          if pattern.matches(pkg_name) {
-            expanded_fixed_group.push(pkg_name.clone());
-          }
-          // println!(
-          //   "The package or glob expression \"{pkg_name}\" specified in the `{}` option does not match any package in the project. You may have misspelled the package name or provided an invalid glob expression. Note that glob expressions must be defined according to https://docs.rs/glob/0.3.1/glob/struct.Pattern.html.",
-          //   serde_json::to_string(&r#type).unwrap_or_default()
-          // )
-        });
+      for pkg_name in match_packages(&fixed_group_item, pkg_names, option, errors) {
+        if !expanded_fixed_group.contains(&pkg_name) {
+          expanded_fixed_group.push(pkg_name);
+        }
       }
     }
 
     for fixed_pkg_name in expanded_fixed_group.iter() {
-      if found_pkg_names.contains(fixed_pkg_name) {
-        duplicated_pkg_names.insert(fixed_pkg_name.clone());
+      if !found_pkg_names.insert(fixed_pkg_name.clone())
+        && !duplicated_pkg_names.contains(fixed_pkg_name)
+      {
+        duplicated_pkg_names.push(fixed_pkg_name.clone());
       }
-      found_pkg_names.insert(fixed_pkg_name.clone());
     }
 
     fixed.push(expanded_fixed_group);
+  }
 
-    if !duplicated_pkg_names.is_empty() {
-      for duplicated_pkg_name in duplicated_pkg_names.iter() {
-        println!("The package \"{duplicated_pkg_name}\" is defined in multiple sets of fixed packages. Packages can only be defined in a single set of fixed packages. If you are using glob expressions, make sure that they are valid according to https://docs.rs/glob/0.3.1/glob/struct.Pattern.html.")
-      }
-    }
+  for duplicated_pkg_name in duplicated_pkg_names {
+    errors.push(ConfigError::DuplicateInGroups(duplicated_pkg_name, r#type));
   }
   fixed
 }
@@ -132,4 +516,324 @@ mod test {
       }
     }
   }
+
+  fn packages(dependencies: &[(&str, &[&str])]) -> Packages {
+    let package = |name: &str, deps: &[&str]| fcsr_pkg::packages::Package {
+      package_json: fcsr_pkg::PkgJson {
+        dependencies: Some(
+          deps
+            .iter()
+            .map(|dep| (dep.to_string(), String::from("^1.0.0")))
+            .collect(),
+        ),
+        ..fcsr_pkg::PkgJson::new(name.to_string(), String::from("1.0.0"))
+      },
+      dir: std::path::PathBuf::from(name),
+      kind: fcsr_pkg::packages::PackageKind::Npm,
+    };
+    Packages {
+      tool: fcsr_pkg::packages::Tool::Pnpm,
+      catalogs: Default::default(),
+      root: package("root", &[]),
+      packages: dependencies
+        .iter()
+        .map(|(name, deps)| package(name, deps))
+        .collect(),
+    }
+  }
+
+  fn written(json: serde_json::Value) -> WrittenConfig {
+    serde_json::from_value(json).unwrap()
+  }
+
+  #[test]
+  fn test_parse_defaults() {
+    let config = parse(written(serde_json::json!({})), &packages(&[])).unwrap();
+    assert_eq!(
+      config.changelog,
+      ConfigContainer::Tuple(String::from("@changesets/cli/changelog"), None)
+    );
+    assert_eq!(config.commit, ConfigContainer::Bool(false));
+    assert_eq!(config.access, AccessType::Restricted);
+    assert_eq!(config.base_branch, "master");
+    assert_eq!(
+      config.private_packages,
+      PrivatePackages {
+        version: true,
+        tag: false,
+        changesets: PrivateChangesets::Ignore,
+      }
+    );
+    assert!(!config.snapshot.use_calculated_version);
+  }
+
+  #[test]
+  fn test_parse_normalizes_options() {
+    let config = parse(
+      written(serde_json::json!({
+        "changelog": ["@changesets/changelog-github", {"repo": "a/b"}],
+        "commit": true,
+        "access": "private",
+        "fixed": [["pkg-*"]],
+        "ignore": ["docs"],
+        "privatePackages": false,
+        "___experimentalUnsafeOptions_WILL_CHANGE_IN_PATCH": {
+          "useCalculatedVersionForSnapshots": true,
+          "updateInternalDependents": "always"
+        },
+        "tagPackages": [{"dir": "charts/api"}]
+      })),
+      &packages(&[("pkg-a", &[]), ("pkg-b", &[]), ("docs", &["pkg-a"])]),
+    )
+    .unwrap();
+
+    assert_eq!(
+      config.changelog,
+      ConfigContainer::Tuple(
+        String::from("@changesets/changelog-github"),
+        Some(serde_json::json!({"repo": "a/b"}))
+      )
+    );
+    assert_eq!(
+      config.commit,
+      ConfigContainer::Tuple(
+        String::from("@changesets/cli/commit"),
+        Some(serde_json::json!({"skipCI": "version"}))
+      )
+    );
+    assert_eq!(config.access, AccessType::Restricted);
+    assert_eq!(config.fixed, vec![vec!["pkg-a", "pkg-b"]]);
+    assert_eq!(config.ignore, vec!["docs"]);
+    assert!(!config.private_packages.version);
+    assert!(config.snapshot.use_calculated_version);
+    assert_eq!(
+      config
+        .experimental_unsafe_options
+        .update_internal_dependents,
+      UpdateInternalDependents::Always
+    );
+    assert_eq!(config.package_sources.tag_packages[0].tag_prefix, "api@");
+  }
+
+  #[test]
+  fn test_package_options() {
+    let config = parse(
+      written(serde_json::json!({
+        "changelog": false,
+        "packages": {
+          "@tools/*": {"publish": false, "changelog": true},
+          "@tools/cli": {"changelog": "docs/CHANGES.md", "tagFormat": "cli-v{version}"},
+          "root": {"tagFormat": "v{version}"}
+        }
+      })),
+      &packages(&[("@tools/cli", &[]), ("@tools/lint", &[]), ("app", &[])]),
+    )
+    .unwrap();
+
+    let cli = config.package_options("@tools/cli");
+    assert_eq!(cli.changelog.as_deref(), Some("docs/CHANGES.md"));
+    assert!(!cli.publish);
+    assert_eq!(
+      config::format_tag(cli.tag_format.as_deref().unwrap(), "@tools/cli", "1.2.0"),
+      "cli-v1.2.0"
+    );
+    let lint = config.package_options("@tools/lint");
+    assert_eq!(lint.changelog.as_deref(), Some("CHANGELOG.md"));
+    assert_eq!(lint.tag_format, None);
+    let app = config.package_options("app");
+    assert_eq!(app.changelog, None);
+    assert!(app.publish);
+
+    let errors = parse(
+      written(serde_json::json!({"packages": {"ap": {"publish": false}}})),
+      &packages(&[("app", &[])]),
+    )
+    .unwrap_err();
+    assert!(matches!(
+      errors.0[0],
+      ConfigError::NoMatch(_, "packages", Some(ref suggestion)) if suggestion == "app"
+    ));
+    let errors = parse(
+      written(serde_json::json!({
+        "tagPackages": [{"dir": "charts/api"}],
+        "packages": {"*": {"tagFormat": "v{version}"}}
+      })),
+      &packages(&[("api", &[]), ("app", &[])]),
+    )
+    .unwrap_err();
+    assert_eq!(errors.0.len(), 1);
+    assert!(matches!(
+      errors.0[0],
+      ConfigError::TagPackageTagFormat(ref package) if package == "api"
+    ));
+  }
+
+  #[test]
+  fn test_parse_collects_all_errors() {
+    let errors = parse(
+      written(serde_json::json!({
+        "changelog": [false],
+        "fixed": [["a", "b"], ["b"]],
+        "linked": [["a"]],
+        "ignore": ["a", "[c"],
+        "lint": {"deps": {"allow": ["[d"]}}
+      })),
+      &packages(&[("a", &[]), ("b", &["a"])]),
+    )
+    .unwrap_err();
+
+    let messages: Vec<_> = errors.0.iter().map(|error| format!("{error:?}")).collect();
+    assert_eq!(errors.0.len(), 6, "{messages:#?}");
+    assert!(
+      matches!(errors.0[0], ConfigError::DuplicateInGroups(ref name, ParsePackageGroupType::Fixed) if name == "b")
+    );
+    assert!(matches!(errors.0[1], ConfigError::FixedAndLinked(ref name) if name == "a"));
+    assert!(matches!(errors.0[2], ConfigError::InvalidGlob(ref glob, "ignore", _) if glob == "[c"));
+    assert!(
+      matches!(errors.0[3], ConfigError::IgnoredDependency(ref dependent, ref ignored) if dependent == "b" && ignored == "a")
+    );
+    assert!(matches!(
+      errors.0[4],
+      ConfigError::InvalidGlob(ref glob, "lint.deps.allow", _) if glob == "[d"
+    ));
+    assert!(matches!(
+      errors.0[5],
+      ConfigError::InvalidContainer("changelog", _)
+    ));
+  }
+
+  #[test]
+  fn test_parse_reports_unmatched_globs() {
+    let errors = parse(
+      written(serde_json::json!({
+        "fixed": [["@scope/core", "@scope/clii"]],
+        "linked": [["@other/*"]],
+        "ignore": ["docs"]
+      })),
+      &packages(&[("@scope/core", &[]), ("@scope/cli", &[]), ("website", &[])]),
+    )
+    .unwrap_err();
+
+    let diagnostics: Vec<_> = errors
+      .diagnostics()
+      .into_iter()
+      .map(|diagnostic| (diagnostic.code, diagnostic.help))
+      .collect();
+    assert_eq!(
+      diagnostics,
+      vec![
+        (
+          "config/no-match",
+          Some(String::from("Did you mean \"@scope/cli\"?"))
+        ),
+        ("config/no-match", None),
+        ("config/no-match", None),
+      ]
+    );
+    assert!(errors.0[0].to_string().contains("`fixed` option"));
+    assert!(errors.0[2].to_string().contains("\"docs\""));
+  }
+
+  #[test]
+  fn test_extend_ignore() {
+    let packages = packages(&[
+      ("@scope/core", &[]),
+      ("@scope/cli", &["@scope/core"]),
+      ("docs", &[]),
+      ("website", &[]),
+    ]);
+    let mut config = parse(
+      written(serde_json::json!({ "ignore": ["docs"] })),
+      &packages,
+    )
+    .unwrap();
+
+    extend_ignore(&mut config, &[], &packages).unwrap();
+    assert_eq!(config.ignore, vec!["docs"]);
+    extend_ignore(&mut config, &[String::from("@scope/*")], &packages).unwrap();
+    assert_eq!(config.ignore, vec!["docs", "@scope/core", "@scope/cli"]);
+
+    let errors = extend_ignore(
+      &mut config,
+      &[String::from("websit"), String::from("@other/*")],
+      &packages,
+    )
+    .unwrap_err();
+    assert!(matches!(
+      errors.0[0],
+      ConfigError::NoMatch(_, "--ignore", Some(ref suggestion)) if suggestion == "website"
+    ));
+    assert!(matches!(
+      errors.0[1],
+      ConfigError::NoMatch(_, "--ignore", None)
+    ));
+    assert!(errors.0[1].to_string().contains("`--ignore` option"));
+
+    let mut config = parse(written(serde_json::json!({})), &packages).unwrap();
+    let errors = extend_ignore(&mut config, &[String::from("@scope/core")], &packages).unwrap_err();
+    assert!(matches!(
+      errors.0[..],
+      [ConfigError::IgnoredDependency(ref dependent, ref ignored)]
+        if dependent == "@scope/cli" && ignored == "@scope/core"
+    ));
+  }
+
+  #[test]
+  fn test_edit_distance() {
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+    assert_eq!(edit_distance("", "abc"), 3);
+    assert_eq!(edit_distance("pkg-a", "pkg-a"), 0);
+  }
+
+  #[test]
+  fn test_load_config() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join(".changeset")).unwrap();
+    std::fs::write(
+      dir.path().join(".changeset/config.json"),
+      r#"{"baseBranch":"main","pythonPackages":["py/*"]}"#,
+    )
+    .unwrap();
+
+    let sources = load_package_sources(dir.path()).unwrap();
+    assert_eq!(sources.python_packages, vec!["py/*"]);
+    let config = load_config(dir.path(), &packages(&[])).unwrap();
+    assert_eq!(config.base_branch, "main");
+    assert!(matches!(
+      load_config(&dir.path().join("missing"), &packages(&[])),
+      Err(ConfigErrors(errors)) if matches!(errors[..], [ConfigError::Read(_)])
+    ));
+
+    std::fs::write(
+      dir.path().join(".changeset/config.json"),
+      r#"{"baseBranch":1,"fixed":[["a"],[false]]}"#,
+    )
+    .unwrap();
+    let errors = load_config(dir.path(), &packages(&[])).unwrap_err();
+    let messages: Vec<_> = errors
+      .diagnostics()
+      .into_iter()
+      .map(|diagnostic| (diagnostic.code, diagnostic.message))
+      .collect();
+    let path = dir.path().join(".changeset/config.json");
+    assert_eq!(
+      messages,
+      vec![
+        (
+          "config/schema",
+          format!(
+            "Invalid config {}: `baseBranch` should be string or null, found integer",
+            path.display()
+          )
+        ),
+        (
+          "config/schema",
+          format!(
+            "Invalid config {}: `fixed[1][0]` should be string, found boolean",
+            path.display()
+          )
+        ),
+      ]
+    );
+  }
 }
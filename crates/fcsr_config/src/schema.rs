@@ -0,0 +1,313 @@
+use crate::written::WrittenConfig;
+use serde_json::{Map, Value};
+use std::fmt::Display;
+
+/// Old spellings still read through a serde alias, with the option they stand for.
+pub(crate) const DEPRECATED_OPTIONS: &[(&str, &str)] = &[(
+  "bumpVersionWithWorkspaceProtocolOnly",
+  "bumpVersionsWithWorkspaceProtocolOnly",
+)];
+
+pub fn config_schema() -> Value {
+  let mut schema = serde_json::to_value(schemars::schema_for!(WrittenConfig)).unwrap_or_default();
+  if let Some(properties) = schema.get_mut("properties").and_then(Value::as_object_mut) {
+    for (old, option) in DEPRECATED_OPTIONS {
+      let Some(mut property) = properties.get(*option).cloned() else {
+        continue;
+      };
+      if let Some(property) = property.as_object_mut() {
+        property.insert("deprecated".to_string(), Value::Bool(true));
+        property.insert(
+          "description".to_string(),
+          Value::String(format!("Deprecated, use `{option}` instead.")),
+        );
+      }
+      properties.insert(old.to_string(), property);
+    }
+  }
+  schema
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaError {
+  pub path: String,
+  pub message: String,
+}
+
+impl Display for SchemaError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    if self.path.is_empty() {
+      write!(f, "{}", self.message)
+    } else {
+      write!(f, "`{}` {}", self.path, self.message)
+    }
+  }
+}
+
+fn type_name(value: &Value) -> &'static str {
+  match value {
+    Value::Null => "null",
+    Value::Bool(_) => "boolean",
+    Value::Number(number) if number.is_f64() => "number",
+    Value::Number(_) => "integer",
+    Value::String(_) => "string",
+    Value::Array(_) => "array",
+    Value::Object(_) => "object",
+  }
+}
+
+fn matches_type(expected: &str, value: &Value) -> bool {
+  let actual = type_name(value);
+  expected == actual || (expected == "number" && actual == "integer")
+}
+
+fn child_path(path: &str, key: &str) -> String {
+  if path.is_empty() {
+    key.to_string()
+  } else {
+    format!("{path}.{key}")
+  }
+}
+
+struct Validator<'a> {
+  root: &'a Value,
+}
+
+impl<'a> Validator<'a> {
+  fn resolve(&self, schema: &'a Value) -> &'a Value {
+    match schema.get("$ref").and_then(Value::as_str) {
+      Some(reference) => self
+        .root
+        .pointer(reference.trim_start_matches('#'))
+        .map(|schema| self.resolve(schema))
+        .unwrap_or(&Value::Bool(true)),
+      None => schema,
+    }
+  }
+
+  fn types(&self, schema: &'a Value) -> Vec<&'a str> {
+    let schema = self.resolve(schema);
+    if let Some(branches) = schema.get("anyOf").and_then(Value::as_array) {
+      return branches
+        .iter()
+        .flat_map(|branch| self.types(branch))
+        .collect();
+    }
+    match schema.get("type") {
+      Some(Value::String(r#type)) => vec![r#type.as_str()],
+      Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+      _ if schema.get("enum").is_some() => vec!["string"],
+      _ => vec!["any"],
+    }
+  }
+
+  fn accepts_type(&self, schema: &'a Value, value: &Value) -> bool {
+    self
+      .types(schema)
+      .iter()
+      .any(|r#type| *r#type == "any" || matches_type(r#type, value))
+  }
+
+  fn check(&self, schema: &'a Value, value: &Value, path: &str, errors: &mut Vec<SchemaError>) {
+    let error = |message: String| SchemaError {
+      path: path.to_string(),
+      message,
+    };
+    let schema = match self.resolve(schema) {
+      Value::Bool(true) => return,
+      Value::Object(schema) => schema,
+      _ => return errors.push(error(String::from("is not allowed"))),
+    };
+
+    if let Some(branches) = schema.get("anyOf").and_then(Value::as_array) {
+      return self.check_any_of(branches, value, path, errors);
+    }
+
+    if let Some(types) = schema.get("type") {
+      let types: Vec<&str> = match types {
+        Value::String(r#type) => vec![r#type.as_str()],
+        types => types
+          .as_array()
+          .into_iter()
+          .flatten()
+          .filter_map(Value::as_str)
+          .collect(),
+      };
+      if !types.iter().any(|r#type| matches_type(r#type, value)) {
+        return errors.push(error(format!(
+          "should be {}, found {}",
+          types.join(" or "),
+          type_name(value)
+        )));
+      }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+      if !allowed.contains(value) {
+        let allowed: Vec<_> = allowed.iter().map(Value::to_string).collect();
+        return errors.push(error(format!(
+          "should be one of {}, found {value}",
+          allowed.join(", ")
+        )));
+      }
+    }
+
+    match value {
+      Value::Object(object) => self.check_object(schema, object, path, errors),
+      Value::Array(items) => match schema.get("items") {
+        Some(Value::Array(item_schemas)) => {
+          for (index, (item_schema, item)) in item_schemas.iter().zip(items).enumerate() {
+            self.check(item_schema, item, &format!("{path}[{index}]"), errors);
+          }
+        }
+        Some(item_schema) => {
+          for (index, item) in items.iter().enumerate() {
+            self.check(item_schema, item, &format!("{path}[{index}]"), errors);
+          }
+        }
+        None => {}
+      },
+      _ => {}
+    }
+  }
+
+  fn check_any_of(
+    &self,
+    branches: &'a [Value],
+    value: &Value,
+    path: &str,
+    errors: &mut Vec<SchemaError>,
+  ) {
+    let mut best: Option<Vec<SchemaError>> = None;
+    for branch in branches
+      .iter()
+      .filter(|branch| self.accepts_type(branch, value))
+    {
+      let mut branch_errors = vec![];
+      self.check(branch, value, path, &mut branch_errors);
+      if branch_errors.is_empty() {
+        return;
+      }
+      if best
+        .as_ref()
+        .is_none_or(|best| branch_errors.len() < best.len())
+      {
+        best = Some(branch_errors);
+      }
+    }
+
+    match best {
+      Some(best) => errors.extend(best),
+      None => {
+        let mut types: Vec<_> = branches
+          .iter()
+          .flat_map(|branch| self.types(branch))
+          .collect();
+        types.dedup();
+        errors.push(SchemaError {
+          path: path.to_string(),
+          message: format!(
+            "should be {}, found {}",
+            types.join(" or "),
+            type_name(value)
+          ),
+        });
+      }
+    }
+  }
+
+  fn check_object(
+    &self,
+    schema: &'a Map<String, Value>,
+    object: &Map<String, Value>,
+    path: &str,
+    errors: &mut Vec<SchemaError>,
+  ) {
+    for required in schema
+      .get("required")
+      .and_then(Value::as_array)
+      .into_iter()
+      .flatten()
+      .filter_map(Value::as_str)
+    {
+      if !object.contains_key(required) {
+        errors.push(SchemaError {
+          path: path.to_string(),
+          message: format!("is missing the required property `{required}`"),
+        });
+      }
+    }
+
+    let properties = schema.get("properties").and_then(Value::as_object);
+    for (key, value) in object {
+      let child = child_path(path, key);
+      match properties.and_then(|properties| properties.get(key)) {
+        Some(property) => self.check(property, value, &child, errors),
+        None => match schema.get("additionalProperties") {
+          Some(Value::Bool(false)) => errors.push(SchemaError {
+            path: child,
+            message: String::from("is not a known option"),
+          }),
+          Some(additional) => self.check(additional, value, &child, errors),
+          None => {}
+        },
+      }
+    }
+  }
+}
+
+pub fn validate(schema: &Value, value: &Value) -> Vec<SchemaError> {
+  let mut errors = vec![];
+  Validator { root: schema }.check(schema, value, "", &mut errors);
+  errors
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use serde_json::json;
+
+  fn messages(value: Value) -> Vec<String> {
+    validate(&config_schema(), &value)
+      .iter()
+      .map(ToString::to_string)
+      .collect()
+  }
+
+  #[test]
+  fn accepts_valid_configs() {
+    let default = serde_json::to_value(WrittenConfig::default()).unwrap();
+    assert!(messages(default).is_empty());
+    assert!(messages(json!({
+      "changelog": ["@changesets/changelog-github", {"repo": "a/b"}],
+      "privatePackages": {"version": true},
+      "tagPackages": [{"dir": "charts/api"}]
+    }))
+    .is_empty());
+    assert!(messages(json!({"bumpVersionWithWorkspaceProtocolOnly": true})).is_empty());
+  }
+
+  #[test]
+  fn reports_paths() {
+    assert_eq!(
+      messages(json!({
+        "fixed": [["a", 1]],
+        "access": "everyone",
+        "baseBranh": "main",
+        "snapshot": {"useCalculatedVersion": "yes"},
+        "tagPackages": [{"name": "api"}],
+        "changelog": 1,
+        "packages": {"app": {"access": "public"}}
+      })),
+      vec![
+        "`fixed[0][1]` should be string, found integer",
+        "`access` should be one of \"public\", \"restricted\", \"private\", found \"everyone\"",
+        "`baseBranh` is not a known option",
+        "`snapshot.useCalculatedVersion` should be boolean or null, found string",
+        "`tagPackages[0]` is missing the required property `dir`",
+        "`changelog` should be boolean or array or string or null, found integer",
+        "`packages.app.access` is not a known option",
+      ]
+    );
+  }
+}
@@ -0,0 +1,243 @@
+use crate::written::ReadConfigError;
+use fcsr_pkg::json_edit::JsonDocument;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+pub const PACKAGE_JSON_KEY: &str = "changeset";
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfigSource {
+  Json,
+  Jsonc,
+  Toml,
+  PackageJson,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigOrigin {
+  Default,
+  File(PathBuf),
+  Extends(PathBuf),
+  Env(&'static str),
+}
+
+impl ConfigOrigin {
+  pub fn display(&self, root: &Path) -> String {
+    let relative = |path: &PathBuf| {
+      path
+        .strip_prefix(root)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+    };
+    match self {
+      Self::Default => String::from("default"),
+      Self::File(path) => format!("file {}", relative(path)),
+      Self::Extends(path) => format!("extends {}", relative(path)),
+      Self::Env(var) => format!("env {var}"),
+    }
+  }
+}
+
+/// One config object and where it came from, layers later in a list override earlier ones.
+#[derive(Debug, Clone)]
+pub struct ConfigLayer {
+  pub origin: ConfigOrigin,
+  pub value: Value,
+}
+
+/// Finds the layer that last set `path`, or a shorthand like `privatePackages: false` above it.
+pub fn origin_of(layers: &[ConfigLayer], path: &[&str]) -> ConfigOrigin {
+  let sets = |layer: &&ConfigLayer| {
+    let mut value = &layer.value;
+    for key in path {
+      match value {
+        Value::Object(object) => match object.get(*key) {
+          Some(child) => value = child,
+          None => return false,
+        },
+        _ => return true,
+      }
+    }
+    true
+  };
+  layers
+    .iter()
+    .rev()
+    .find(sets)
+    .map_or(ConfigOrigin::Default, |layer| layer.origin.clone())
+}
+
+pub const CONFIG_SOURCES: [ConfigSource; 4] = [
+  ConfigSource::Json,
+  ConfigSource::Jsonc,
+  ConfigSource::Toml,
+  ConfigSource::PackageJson,
+];
+
+impl ConfigSource {
+  pub fn path(self, root: &Path) -> PathBuf {
+    let changeset_dir = root.join(".changeset");
+    match self {
+      Self::Json => changeset_dir.join("config.json"),
+      Self::Jsonc => changeset_dir.join("config.jsonc"),
+      Self::Toml => changeset_dir.join("config.toml"),
+      Self::PackageJson => root.join("package.json"),
+    }
+  }
+
+  pub fn read_value(self, root: &Path) -> Result<Option<Value>, ReadConfigError> {
+    let path = self.path(root);
+    let source = match std::fs::read_to_string(&path) {
+      Ok(source) => source,
+      Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+      Err(error) => return Err(ReadConfigError::Io(path, error)),
+    };
+
+    match self {
+      Self::Json => serde_json::from_str(&source)
+        .map(Some)
+        .map_err(|error| ReadConfigError::Json(path, error)),
+      Self::Jsonc => JsonDocument::parse(&source)
+        .map(|document| Some(document.to_value()))
+        .map_err(|error| ReadConfigError::Jsonc(path, error)),
+      Self::Toml => toml_edit::de::from_str(&source)
+        .map(Some)
+        .map_err(|error| ReadConfigError::Toml(path, error)),
+      Self::PackageJson => {
+        let mut package_json: Value =
+          serde_json::from_str(&source).map_err(|error| ReadConfigError::Json(path, error))?;
+        Ok(package_json.get_mut(PACKAGE_JSON_KEY).map(Value::take))
+      }
+    }
+  }
+
+  pub fn locate(root: &Path) -> Result<(Self, Value), ReadConfigError> {
+    let mut found = vec![];
+    for source in CONFIG_SOURCES {
+      if let Some(value) = source.read_value(root)? {
+        found.push((source, value));
+      }
+    }
+
+    match found.len() {
+      0 => Err(ReadConfigError::NotFound(Self::Json.path(root))),
+      1 => Ok(found.remove(0)),
+      _ => Err(ReadConfigError::Multiple(
+        found.iter().map(|(source, _)| source.path(root)).collect(),
+      )),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::written::WrittenConfig;
+  use fcsr_test_utils::write;
+
+  #[test]
+  fn reads_every_source() {
+    let cases = [
+      (
+        ".changeset/config.json",
+        r#"{"baseBranch":"main"}"#,
+        ConfigSource::Json,
+      ),
+      (
+        ".changeset/config.jsonc",
+        "{\n  // the default branch\n  \"baseBranch\": \"main\",\n}\n",
+        ConfigSource::Jsonc,
+      ),
+      (
+        ".changeset/config.toml",
+        "baseBranch = \"main\"\n",
+        ConfigSource::Toml,
+      ),
+      (
+        "package.json",
+        r#"{"name":"root","changeset":{"baseBranch":"main"}}"#,
+        ConfigSource::PackageJson,
+      ),
+    ];
+    for (path, content, expected) in cases {
+      let dir = tempfile::tempdir().unwrap();
+      write(dir.path(), path, content);
+
+      let (config, source) = WrittenConfig::read_with_source(dir.path()).unwrap();
+      assert_eq!(source, expected);
+      assert_eq!(config.base_branch.as_deref(), Some("main"));
+    }
+  }
+
+  #[test]
+  fn rejects_missing_and_multiple_sources() {
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "package.json", r#"{"name":"root"}"#);
+    assert!(matches!(
+      ConfigSource::locate(dir.path()),
+      Err(ReadConfigError::NotFound(_))
+    ));
+
+    write(dir.path(), ".changeset/config.json", "{}");
+    write(dir.path(), ".changeset/config.toml", "");
+    match ConfigSource::locate(dir.path()) {
+      Err(ReadConfigError::Multiple(paths)) => assert_eq!(
+        paths,
+        vec![
+          ConfigSource::Json.path(dir.path()),
+          ConfigSource::Toml.path(dir.path())
+        ]
+      ),
+      result => panic!("{result:?}"),
+    }
+  }
+
+  #[test]
+  fn finds_value_origins() {
+    let layer = |origin, value| ConfigLayer { origin, value };
+    let layers = [
+      layer(
+        ConfigOrigin::Extends(PathBuf::from("base.json")),
+        serde_json::json!({"baseBranch": "main", "privatePackages": false}),
+      ),
+      layer(
+        ConfigOrigin::File(PathBuf::from("config.json")),
+        serde_json::json!({"access": "public"}),
+      ),
+      layer(
+        ConfigOrigin::Env("FCSR_BASE_BRANCH"),
+        serde_json::json!({"baseBranch": "next"}),
+      ),
+    ];
+    assert_eq!(
+      origin_of(&layers, &["baseBranch"]),
+      ConfigOrigin::Env("FCSR_BASE_BRANCH")
+    );
+    assert_eq!(
+      origin_of(&layers, &["access"]),
+      ConfigOrigin::File(PathBuf::from("config.json"))
+    );
+    assert_eq!(
+      origin_of(&layers, &["privatePackages", "tag"]),
+      ConfigOrigin::Extends(PathBuf::from("base.json"))
+    );
+    assert_eq!(origin_of(&layers, &["ignore"]), ConfigOrigin::Default);
+  }
+
+  #[test]
+  fn reports_package_json_paths() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+      dir.path(),
+      "package.json",
+      r#"{"name":"root","changeset":{"access":"everyone"}}"#,
+    );
+    match WrittenConfig::read(dir.path()) {
+      Err(ReadConfigError::Schema(_, errors)) => assert_eq!(errors[0].path, "changeset.access"),
+      result => panic!("{result:?}"),
+    }
+  }
+}
@@ -1,15 +1,28 @@
 use super::PackageGroup;
+use crate::{
+  env::{env_layers, read_env},
+  extends::{extends_chain, merge_layers},
+  schema::{config_schema, validate, SchemaError, DEPRECATED_OPTIONS},
+  source::{ConfigLayer, ConfigOrigin, ConfigSource, PACKAGE_JSON_KEY},
+};
 use fcsr_pkg::access_type;
+use fcsr_pkg::json_edit::JsonEditError;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::{
+  collections::BTreeMap,
+  path::{Path, PathBuf},
+};
+use thiserror::Error;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(untagged)]
 pub enum ConfigContainerTuple {
   String(String),
   Value(serde_json::Value),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(untagged)]
 pub enum ConfigContainer {
   Bool(bool),
@@ -17,89 +30,332 @@ pub enum ConfigContainer {
   String(String),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
 pub enum PrivatePackagesEnum {
   Bool(bool),
   PrivatePackages(PrivatePackages),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum PrivateChangesets {
+  #[default]
+  Ignore,
+  Reject,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct PrivatePackages {
-  version: Option<bool>,
-  tag: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub version: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub tag: Option<bool>,
+  /// Whether changesets releasing private packages are ignored or rejected when `version` is
+  /// false.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub changesets: Option<PrivateChangesets>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub enum UpdateInternalDependencies {
   Patch,
   Minor,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Snapshot {
-  use_calculated_version: Option<bool>,
-  prerelease_template: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub use_calculated_version: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub prerelease_template: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub enum UpdateInternalDependents {
   Always,
   OutOfRange,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ExperimentalOptions {
-  only_update_peer_dependents_when_out_of_range: Option<bool>,
-  update_internal_dependents: Option<UpdateInternalDependents>,
-  use_calculated_version_for_snapshots: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub only_update_peer_dependents_when_out_of_range: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub update_internal_dependents: Option<UpdateInternalDependents>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub use_calculated_version_for_snapshots: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TagPackage {
+  pub dir: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub name: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub tag_prefix: Option<String>,
+}
+
+impl TagPackage {
+  pub fn name(&self) -> String {
+    self.name.clone().unwrap_or_else(|| {
+      Path::new(&self.dir)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| self.dir.clone())
+    })
+  }
+
+  pub fn tag_prefix(&self) -> String {
+    self
+      .tag_prefix
+      .clone()
+      .unwrap_or_else(|| format!("{}@", self.name()))
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum PackageChangelog {
+  Bool(bool),
+  Path(String),
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct PackageOverrides {
+  /// `false` to skip the changelog, or its path relative to the package.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub changelog: Option<PackageChangelog>,
+  /// The git tag created for a release, e.g. `v{version}` or `{name}@{version}`.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub tag_format: Option<String>,
+  /// `false` to never tag or publish the package.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub publish: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct DependencyLint {
+  /// Ranges every package must declare an external dependency with, by dependency name. Python
+  /// names are normalized and pins that are not valid in an ecosystem are skipped there.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub pin: Option<BTreeMap<String, String>>,
+  /// External dependencies (or globs) allowed to be declared with different ranges.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub allow: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct LintOptions {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub deps: Option<DependencyLint>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields, title = "fcsr config")]
 pub struct WrittenConfig {
-  #[serde(rename = "$schema")]
+  #[serde(rename = "$schema", skip_serializing_if = "Option::is_none")]
   pub schema: Option<String>,
+  /// A config file (relative to this one) or preset package whose options this config overrides.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub extends: Option<String>,
+  /// `false`, a changelog generator module, or a `[module, options]` tuple.
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub changelog: Option<ConfigContainer>,
+  /// `false`, `true`, a commit message module, or a `[module, options]` tuple.
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub commit: Option<ConfigContainer>,
+  /// Groups of packages (or globs) that are always released together with the same version.
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub fixed: Option<Vec<PackageGroup>>,
+  /// Groups of packages (or globs) whose released members share the highest version.
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub linked: Option<Vec<PackageGroup>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub access: Option<access_type::AccessType>,
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub base_branch: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub changed_file_patterns: Option<PackageGroup>,
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub private_packages: Option<PrivatePackagesEnum>,
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub update_internal_dependencies: Option<UpdateInternalDependencies>,
+  /// Packages (or globs) that are never versioned.
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub ignore: Option<Vec<String>>,
-  pub bump_version_with_workspace_protocol_only: Option<bool>,
+  #[serde(
+    alias = "bumpVersionWithWorkspaceProtocolOnly",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub bump_versions_with_workspace_protocol_only: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub snapshot: Option<Snapshot>,
-  #[serde(rename(serialize = "___experimentalUnsafeOptions_WILL_CHANGE_IN_PATCH"))]
+  #[serde(
+    rename = "___experimentalUnsafeOptions_WILL_CHANGE_IN_PATCH",
+    skip_serializing_if = "Option::is_none"
+  )]
   pub experimental_unsafe_options_will_change_in_path: Option<ExperimentalOptions>,
+  /// Globs of directories containing a `pyproject.toml` to version as Python packages.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub python_packages: Option<PackageGroup>,
+  /// Directories versioned only through git tags.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub tag_packages: Option<Vec<TagPackage>>,
+  /// Options overriding the global ones for packages matched by name or glob. Exact names win
+  /// over globs.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub packages: Option<BTreeMap<String, PackageOverrides>>,
+  /// Policies checked by `fcsr lint`.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub lint: Option<LintOptions>,
+}
+
+#[derive(Debug, Error)]
+pub enum ReadConfigError {
+  #[error("Failed to read {0}: {1}")]
+  Io(PathBuf, std::io::Error),
+  #[error("Failed to parse {0}: {1}")]
+  Json(PathBuf, serde_json::Error),
+  #[error("Failed to parse {0}: {1}")]
+  Jsonc(PathBuf, JsonEditError),
+  #[error("Failed to parse {0}: {1}")]
+  Toml(PathBuf, toml_edit::de::Error),
+  #[error("Invalid config {0}: {}", .1.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+  Schema(PathBuf, Vec<SchemaError>),
+  #[error("No config found at {0}. Run `fcsr init` to create one")]
+  NotFound(PathBuf),
+  #[error("Could not find the config \"{0}\" extended by {1}")]
+  ExtendsNotFound(String, PathBuf),
+  #[error("Config files extend each other in a cycle: {}", .0.iter().map(|path| path.display().to_string()).collect::<Vec<_>>().join(" -> "))]
+  ExtendsCycle(Vec<PathBuf>),
+  #[error("Invalid {0}: {1}")]
+  Env(&'static str, String),
+  #[error("Found more than one config: {}. Please keep only one of them", .0.iter().map(|path| path.display().to_string()).collect::<Vec<_>>().join(", "))]
+  Multiple(Vec<PathBuf>),
+}
+
+impl ReadConfigError {
+  pub fn path(&self) -> Option<&Path> {
+    match self {
+      Self::Io(path, _)
+      | Self::Json(path, _)
+      | Self::Jsonc(path, _)
+      | Self::Toml(path, _)
+      | Self::Schema(path, _)
+      | Self::NotFound(path)
+      | Self::ExtendsNotFound(_, path) => Some(path),
+      Self::ExtendsCycle(paths) | Self::Multiple(paths) => paths.first().map(PathBuf::as_path),
+      Self::Env(..) => None,
+    }
+  }
+}
+
+pub const SCHEMA_FILE: &str = "schema.json";
+
+impl WrittenConfig {
+  pub fn read(root: &Path) -> Result<Self, ReadConfigError> {
+    Self::read_with_source(root).map(|(config, _)| config)
+  }
+
+  pub fn read_with_source(root: &Path) -> Result<(Self, ConfigSource), ReadConfigError> {
+    let (source, layers) = Self::read_layers(root, read_env)?;
+    let path = source.path(root);
+    let value = merge_layers(layers.into_iter().map(|layer| layer.value));
+    let config =
+      serde_json::from_value(value).map_err(|error| ReadConfigError::Json(path, error))?;
+    Ok((config, source))
+  }
+
+  /// Reads the config file, the configs it extends and the environment overrides, in the order
+  /// they apply.
+  pub fn read_layers(
+    root: &Path,
+    env: impl Fn(&str) -> Option<String>,
+  ) -> Result<(ConfigSource, Vec<ConfigLayer>), ReadConfigError> {
+    let (source, value) = ConfigSource::locate(root)?;
+    let path = source.path(root);
+    let mut errors = validate(&config_schema(), &value);
+    if !errors.is_empty() {
+      if source == ConfigSource::PackageJson {
+        for error in errors.iter_mut() {
+          error.path = match error.path.as_str() {
+            "" => PACKAGE_JSON_KEY.to_string(),
+            path => format!("{PACKAGE_JSON_KEY}.{path}"),
+          };
+        }
+      }
+      return Err(ReadConfigError::Schema(path, errors));
+    }
+    let mut layers: Vec<ConfigLayer> = extends_chain(&path, value)?
+      .into_iter()
+      .map(|(file, value)| ConfigLayer {
+        origin: if file == path {
+          ConfigOrigin::File(file)
+        } else {
+          ConfigOrigin::Extends(file)
+        },
+        value,
+      })
+      .collect();
+    for layer in layers.iter_mut() {
+      let Some(object) = layer.value.as_object_mut() else {
+        continue;
+      };
+      for (old, option) in DEPRECATED_OPTIONS {
+        if let Some(value) = object.remove(*old) {
+          object.entry(*option).or_insert(value);
+        }
+      }
+    }
+    layers.extend(
+      env_layers(env)?
+        .into_iter()
+        .map(|(var, value)| ConfigLayer {
+          origin: ConfigOrigin::Env(var),
+          value,
+        }),
+    );
+    Ok((source, layers))
+  }
 }
 
 impl Default for WrittenConfig {
   fn default() -> Self {
     Self {
-      schema: Some(String::from(
-        "https://unpkg.com/@changesets/config@latest/schema.json",
-      )),
+      schema: Some(format!("./{SCHEMA_FILE}")),
+      extends: None,
       changelog: Some(ConfigContainer::String(String::from(
         "@changesets/cli/changelog",
       ))),
       commit: Some(ConfigContainer::Bool(false)),
-      fixed: Some(Vec::from(Vec::new())),
-      linked: Some(Vec::from(Vec::new())),
+      fixed: Some(Vec::new()),
+      linked: Some(Vec::new()),
       access: Some(access_type::AccessType::Restricted),
       base_branch: Some(String::from("master")),
       changed_file_patterns: None,
       private_packages: None,
       update_internal_dependencies: Some(UpdateInternalDependencies::Patch),
       ignore: Some(Vec::new()),
-      bump_version_with_workspace_protocol_only: None,
+      bump_versions_with_workspace_protocol_only: None,
       snapshot: None,
       experimental_unsafe_options_will_change_in_path: None,
+      python_packages: None,
+      tag_packages: None,
+      packages: None,
+      lint: None,
     }
   }
 }
@@ -109,6 +365,7 @@ mod test {
   use super::*;
 
   #[test]
+  #[allow(clippy::redundant_pattern_matching)]
   fn test_written_config_default_serialize() {
     let default_written_config = WrittenConfig::default();
     assert!(matches!(
@@ -118,10 +375,35 @@ mod test {
   }
 
   #[test]
+  #[allow(clippy::redundant_pattern_matching)]
   fn test_written_config_default_deserialize() {
     let config = serde_json::from_str::<WrittenConfig>(
       r#"{"$schema":"https://unpkg.com/@changesets/config@lasest/schema.json","changelog":"@changesets/cli/changelog","commit":false,"fixed":[],"linked":[],"access":"restricted","baseBranch":"master","changedFilePatterns":null,"privatePackages":null,"updateInternalDependencies":"patch","ignore":[],"bumpVersionWithWorkspaceProtocolOnly":null,"snapshot":null,"___experimentalUnsafeOptions_WILL_CHANGE_IN_PATCH":null}"#,
     );
     assert!(matches!(config, Ok(_)))
   }
+
+  #[test]
+  fn test_deprecated_option_spelling() {
+    let config =
+      serde_json::from_str::<WrittenConfig>(r#"{"bumpVersionWithWorkspaceProtocolOnly":true}"#)
+        .unwrap();
+    assert_eq!(
+      config.bump_versions_with_workspace_protocol_only,
+      Some(true)
+    );
+  }
+
+  #[test]
+  fn test_tag_package_defaults() {
+    let config = serde_json::from_str::<WrittenConfig>(
+      r#"{"tagPackages":[{"dir":"charts/api"},{"dir":"go/sdk","name":"go-sdk","tagPrefix":"go/sdk/v"}]}"#,
+    )
+    .unwrap();
+    let tag_packages = config.tag_packages.unwrap();
+    assert_eq!(tag_packages[0].name(), "api");
+    assert_eq!(tag_packages[0].tag_prefix(), "api@");
+    assert_eq!(tag_packages[1].name(), "go-sdk");
+    assert_eq!(tag_packages[1].tag_prefix(), "go/sdk/v");
+  }
 }
@@ -0,0 +1,112 @@
+use std::path::PathBuf;
+
+use crate::packages_options;
+use fcsr_changeset::changeset_dir;
+use fcsr_config::{
+  edit::{get_config_value, parse_path, set_config_value, EditError},
+  env::read_env,
+  load_config, load_package_sources,
+  schema::config_schema,
+  source::origin_of,
+  written::{WrittenConfig, SCHEMA_FILE},
+  ConfigErrors,
+};
+use fcsr_metadata::{Config, ConfigCommand, ConfigGet, ConfigList, ConfigSchema, ConfigSet};
+use fcsr_tools::{find_root, get_packages, FindRootError, PackagesError};
+use serde_json::Value;
+use thiserror::Error;
+
+pub fn run_config(config: Config, pwd: PathBuf) -> anyhow::Result<(), ConfigCommandError> {
+  match config.command {
+    ConfigCommand::Schema(schema) => run_schema(schema, pwd),
+    ConfigCommand::Get(get) => run_get(get, pwd),
+    ConfigCommand::Set(set) => run_set(set, pwd),
+    ConfigCommand::List(list) => run_list(list, pwd),
+  }
+}
+
+fn run_schema(
+  ConfigSchema { write }: ConfigSchema,
+  pwd: PathBuf,
+) -> Result<(), ConfigCommandError> {
+  let mut schema = serde_json::to_string_pretty(&config_schema()).unwrap_or_default();
+  schema.push('\n');
+
+  if !write {
+    print!("{schema}");
+    return Ok(());
+  }
+  let path = changeset_dir(&find_root(pwd)?).join(SCHEMA_FILE);
+  std::fs::write(&path, schema).map_err(|error| ConfigCommandError::Io(path.clone(), error))?;
+  println!("Wrote {}", path.display());
+  Ok(())
+}
+
+fn run_get(ConfigGet { path }: ConfigGet, pwd: PathBuf) -> Result<(), ConfigCommandError> {
+  let value = get_config_value(&find_root(pwd)?, &parse_path(&path)?)?;
+  println!(
+    "{}",
+    serde_json::to_string_pretty(&value).unwrap_or_default()
+  );
+  Ok(())
+}
+
+fn run_set(ConfigSet { path, value }: ConfigSet, pwd: PathBuf) -> Result<(), ConfigCommandError> {
+  let value = serde_json::from_str(&value).unwrap_or(Value::String(value));
+  set_config_value(&find_root(pwd)?, &parse_path(&path)?, value)?;
+  Ok(())
+}
+
+fn list_values(value: &Value, path: &mut Vec<String>, lines: &mut Vec<(Vec<String>, String)>) {
+  match value {
+    Value::Object(object) if !object.is_empty() => {
+      for (key, value) in object {
+        path.push(key.clone());
+        list_values(value, path, lines);
+        path.pop();
+      }
+    }
+    value => lines.push((path.clone(), value.to_string())),
+  }
+}
+
+fn run_list(_: ConfigList, pwd: PathBuf) -> Result<(), ConfigCommandError> {
+  let root = find_root(pwd)?;
+  let packages = get_packages(&root, &packages_options(&load_package_sources(&root)?))?;
+  let config = load_config(&root, &packages)?;
+  let (_, layers) = WrittenConfig::read_layers(&root, read_env).map_err(ConfigErrors::from)?;
+
+  let mut lines = vec![];
+  list_values(
+    &serde_json::to_value(&config).unwrap_or_default(),
+    &mut vec![],
+    &mut lines,
+  );
+  let lines: Vec<_> = lines
+    .into_iter()
+    .map(|(path, value)| {
+      let keys: Vec<&str> = path.iter().map(String::as_str).collect();
+      let origin = origin_of(&layers, &keys).display(&root);
+      (format!("{} = {value}", path.join(".")), origin)
+    })
+    .collect();
+  let width = lines.iter().map(|(line, _)| line.len()).max().unwrap_or(0);
+  for (line, origin) in lines {
+    println!("{line:width$}  # {origin}");
+  }
+  Ok(())
+}
+
+#[derive(Debug, Error)]
+pub enum ConfigCommandError {
+  #[error(transparent)]
+  Root(#[from] FindRootError),
+  #[error(transparent)]
+  Config(#[from] ConfigErrors),
+  #[error(transparent)]
+  Packages(#[from] PackagesError),
+  #[error(transparent)]
+  Edit(#[from] EditError),
+  #[error("Failed to write {0}: {1}")]
+  Io(PathBuf, std::io::Error),
+}
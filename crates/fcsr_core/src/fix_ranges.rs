@@ -0,0 +1,251 @@
+use std::path::PathBuf;
+
+use crate::packages_options;
+use fcsr_config::{
+  dependency_graph::{get_all_dependencies, get_dependency_graph, DependencySpec},
+  load_package_sources, ConfigErrors,
+};
+use fcsr_diagnostic::{Diagnostic, Diagnostics, Severity};
+use fcsr_metadata::FixRanges;
+use fcsr_pkg::{
+  packages::{Package, Packages},
+  ManifestError, RangeUpdate,
+};
+use fcsr_release::update_range;
+use fcsr_tools::{find_root, get_packages, FindRootError, PackagesError};
+use thiserror::Error;
+
+pub fn run_fix_ranges(
+  FixRanges { check }: FixRanges,
+  pwd: PathBuf,
+  diagnostics: &mut Diagnostics,
+) -> anyhow::Result<(), FixRangesError> {
+  let root = find_root(pwd)?;
+  let packages = get_packages(&root, &packages_options(&load_package_sources(&root)?))?;
+  for line in fix_ranges(&packages, check, diagnostics)? {
+    println!("{line}");
+  }
+  Ok(())
+}
+
+pub(crate) struct RangeFix<'a> {
+  pub(crate) package: &'a Package,
+  pub(crate) section: &'static str,
+  pub(crate) name: String,
+  pub(crate) range: String,
+  pub(crate) fixed: String,
+}
+
+impl RangeFix<'_> {
+  pub(crate) fn describe(&self) -> String {
+    format!(
+      r#"{}: {}.{} "{}" -> "{}""#,
+      self.package.package_json.name, self.section, self.name, self.range, self.fixed
+    )
+  }
+}
+
+/// Writes the fixes, one manifest edit per package, and describes each of them.
+pub(crate) fn apply_fixes(fixes: &[RangeFix]) -> Result<Vec<String>, ManifestError> {
+  let mut updated: Vec<&str> = vec![];
+  for fix in fixes.iter() {
+    let name = fix.package.package_json.name.as_str();
+    if updated.contains(&name) {
+      continue;
+    }
+    updated.push(name);
+    let ranges: Vec<_> = fixes
+      .iter()
+      .filter(|other| other.package.package_json.name == name)
+      .map(|other| RangeUpdate {
+        section: other.section,
+        name: &other.name,
+        from: &other.range,
+        to: &other.fixed,
+      })
+      .collect();
+    fix.package.update_dependency_ranges(&ranges)?;
+  }
+  Ok(fixes.iter().map(RangeFix::describe).collect())
+}
+
+/// Ranges that cannot be rewritten are errors with `check`, which has to fail on them.
+fn find_fixes<'a>(
+  packages: &'a Packages,
+  check: bool,
+  diagnostics: &mut Diagnostics,
+) -> Vec<RangeFix<'a>> {
+  let severity = match check {
+    true => Severity::Error,
+    false => Severity::Warning,
+  };
+  let all_packages: Vec<&Package> = packages.packages.iter().chain([&packages.root]).collect();
+  let find = |name: &str| {
+    all_packages
+      .iter()
+      .find(|package| package.package_json.name == name)
+      .copied()
+  };
+
+  let mut fixes = vec![];
+  for edge in get_dependency_graph(packages, None).invalid_edges() {
+    let (Some(package), Some(dependency)) = (find(&edge.from), find(&edge.to)) else {
+      continue;
+    };
+    let Some(name) = get_all_dependencies(&package.package_json)
+      .into_iter()
+      .find(|(kind, name, range)| {
+        *kind == edge.kind
+          && *range == edge.range
+          && DependencySpec::parse(name, range).name == edge.to
+      })
+      .map(|(_, name, _)| name)
+    else {
+      continue;
+    };
+    let duplicate = fixes.iter().any(|fix: &RangeFix| {
+      fix.package.package_json.name == edge.from
+        && fix.section == edge.kind.as_str()
+        && fix.name == name
+        && fix.range == edge.range
+    });
+    if duplicate {
+      continue;
+    }
+
+    let version = &dependency.package_json.version;
+    match update_range(package.kind, &edge.range, version) {
+      Some(fixed) => fixes.push(RangeFix {
+        package,
+        section: edge.kind.as_str(),
+        name,
+        range: edge.range.clone(),
+        fixed,
+      }),
+      None => diagnostics.push(
+        Diagnostic::new(
+          "fix-ranges/unfixable",
+          severity,
+          format!(
+            r#"Cannot rewrite the range "{}" of "{}" in {}"#,
+            edge.range,
+            edge.to,
+            edge.kind.as_str()
+          ),
+        )
+        .with_package(&edge.from)
+        .with_help(format!(
+          r#"Update the range of "{}" by hand so that it includes {version}"#,
+          edge.to
+        )),
+      ),
+    }
+  }
+  fixes
+}
+
+/// Rewrites every internal range that excludes the current version, or reports them with `check`.
+fn fix_ranges(
+  packages: &Packages,
+  check: bool,
+  diagnostics: &mut Diagnostics,
+) -> Result<Vec<String>, FixRangesError> {
+  let fixes = find_fixes(packages, check, diagnostics);
+
+  if check {
+    for fix in fixes.iter() {
+      let mut diagnostic = Diagnostic::error(
+        "fix-ranges/mismatched-range",
+        format!(
+          r#"Range "{}" of "{}" in {} should be "{}""#,
+          fix.range, fix.name, fix.section, fix.fixed
+        ),
+      )
+      .with_package(&fix.package.package_json.name)
+      .with_help("Run `fcsr fix-ranges` to rewrite it");
+      if let Some(path) = fix.package.manifest_path() {
+        diagnostic = diagnostic.with_file(path);
+      }
+      diagnostics.push(diagnostic);
+    }
+    return Ok(vec![]);
+  }
+
+  Ok(apply_fixes(&fixes)?)
+}
+
+#[derive(Debug, Error)]
+pub enum FixRangesError {
+  #[error(transparent)]
+  Root(#[from] FindRootError),
+  #[error(transparent)]
+  Config(#[from] ConfigErrors),
+  #[error(transparent)]
+  Packages(#[from] PackagesError),
+  #[error(transparent)]
+  Manifest(#[from] ManifestError),
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use fcsr_pkg::packages::PackageKind;
+  use fcsr_test_utils::workspace;
+
+  #[test]
+  fn rewrites_mismatched_ranges() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let app = "{\n  \"name\": \"app\",\n  \"version\": \"1.0.0\",\n  \"dependencies\": { \"core\": \"^1.0.0\", \"ui\": \"workspace:~1.0.0\" },\n  \"devDependencies\": { \"core\": \"workspace:*\" },\n  \"peerDependencies\": { \"core\": \">=1 <2\" }\n}\n";
+    let packages = workspace(
+      root,
+      &[
+        ("app", PackageKind::Npm, app),
+        (
+          "core",
+          PackageKind::Npm,
+          r#"{"name":"core","version":"2.1.0"}"#,
+        ),
+        ("ui", PackageKind::Npm, r#"{"name":"ui","version":"1.1.0"}"#),
+      ],
+    );
+
+    let mut diagnostics = Diagnostics::default();
+    assert!(fix_ranges(&packages, true, &mut diagnostics)
+      .unwrap()
+      .is_empty());
+    let codes: Vec<_> = diagnostics
+      .iter()
+      .map(|diagnostic| diagnostic.code)
+      .collect();
+    assert_eq!(
+      codes,
+      vec![
+        "fix-ranges/unfixable",
+        "fix-ranges/mismatched-range",
+        "fix-ranges/mismatched-range"
+      ]
+    );
+    assert!(diagnostics
+      .iter()
+      .all(|diagnostic| diagnostic.severity == Severity::Error));
+    let manifest = root.join("app/package.json");
+    assert_eq!(std::fs::read_to_string(&manifest).unwrap(), app);
+
+    let mut diagnostics = Diagnostics::default();
+    assert_eq!(
+      fix_ranges(&packages, false, &mut diagnostics).unwrap(),
+      vec![
+        r#"app: dependencies.core "^1.0.0" -> "^2.1.0""#,
+        r#"app: dependencies.ui "workspace:~1.0.0" -> "workspace:~1.1.0""#,
+      ]
+    );
+    assert!(!diagnostics.has_errors());
+    assert_eq!(
+      std::fs::read_to_string(&manifest).unwrap(),
+      app
+        .replace("\"^1.0.0\"", "\"^2.1.0\"")
+        .replace("workspace:~1.0.0", "workspace:~1.1.0")
+    );
+  }
+}
@@ -0,0 +1,339 @@
+use std::{
+  collections::{BTreeSet, VecDeque},
+  path::PathBuf,
+};
+
+use crate::packages_options;
+use fcsr_config::{
+  dependency_graph::{get_dependency_graph, DependencyGraph, Edge},
+  load_package_sources, ConfigErrors,
+};
+use fcsr_metadata::{Graph, GraphFormat};
+use fcsr_pkg::packages::{Packages, Tool};
+use fcsr_tools::{find_root, get_packages, FindRootError, PackagesError};
+use serde_json::json;
+use thiserror::Error;
+
+pub fn run_graph(graph: Graph, pwd: PathBuf) -> anyhow::Result<(), GraphError> {
+  let root = find_root(pwd)?;
+  let packages = get_packages(&root, &packages_options(&load_package_sources(&root)?))?;
+  print!("{}", render_graph(&graph, &packages)?);
+  Ok(())
+}
+
+/// The `(source, target)` of an edge, pointing at dependents instead of dependencies if asked.
+fn direction(edge: &Edge, dependents: bool) -> (&str, &str) {
+  match dependents {
+    true => (&edge.to, &edge.from),
+    false => (&edge.from, &edge.to),
+  }
+}
+
+struct GraphView<'a> {
+  dependents: bool,
+  packages: Vec<&'a str>,
+  /// Edges with whether their range includes the current version.
+  edges: Vec<(&'a Edge, bool)>,
+}
+
+impl<'a> GraphView<'a> {
+  fn new(
+    graph: &'a DependencyGraph,
+    packages: &Packages,
+    Graph {
+      dependents,
+      focus,
+      depth,
+      ..
+    }: &Graph,
+  ) -> Result<Self, GraphError> {
+    let edges: Vec<(&Edge, bool)> = graph
+      .edges()
+      .map(|edge| (edge, true))
+      .chain(graph.invalid_edges().iter().map(|edge| (edge, false)))
+      .collect();
+    let direction = |edge: &'a Edge| direction(edge, *dependents);
+
+    let root = packages.root.package_json.name.as_str();
+    let shown: BTreeSet<&str> = match focus {
+      Some(focus) => {
+        let focus = graph
+          .names()
+          .into_iter()
+          .find(|name| name == focus)
+          .ok_or_else(|| GraphError::UnknownPackage(focus.clone()))?;
+        let mut shown = BTreeSet::from([focus]);
+        let mut queue = VecDeque::from([(focus, 0)]);
+        while let Some((name, distance)) = queue.pop_front() {
+          if depth.is_some_and(|depth| distance >= depth) {
+            continue;
+          }
+          for (source, target) in edges.iter().map(|(edge, _)| direction(edge)) {
+            if source == name && shown.insert(target) {
+              queue.push_back((target, distance + 1));
+            }
+          }
+        }
+        shown
+      }
+      None => graph
+        .names()
+        .into_iter()
+        .filter(|name| {
+          *name != root
+            || matches!(packages.tool, Tool::Root)
+            || edges
+              .iter()
+              .any(|(edge, _)| edge.from == root || edge.to == root)
+        })
+        .collect(),
+    };
+
+    Ok(Self {
+      dependents: *dependents,
+      edges: edges
+        .into_iter()
+        .filter(|(edge, _)| {
+          let (source, target) = direction(edge);
+          shown.contains(source) && shown.contains(target)
+        })
+        .collect(),
+      packages: shown.into_iter().collect(),
+    })
+  }
+
+  fn label(edge: &Edge, valid: bool) -> String {
+    match valid {
+      true => edge.kind.as_str().to_string(),
+      false => format!("{} {}", edge.kind.as_str(), edge.range),
+    }
+  }
+
+  fn title(&self) -> &'static str {
+    match self.dependents {
+      true => "dependents",
+      false => "dependencies",
+    }
+  }
+
+  fn dot(&self) -> String {
+    let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut lines = vec![format!("digraph {} {{", self.title())];
+    for package in self.packages.iter() {
+      lines.push(format!("  {};", quote(package)));
+    }
+    for (edge, valid) in self.edges.iter() {
+      let (source, target) = direction(edge, self.dependents);
+      let style = match valid {
+        true => String::new(),
+        false => ", color=\"red\", fontcolor=\"red\"".to_string(),
+      };
+      lines.push(format!(
+        "  {} -> {} [label={}{style}];",
+        quote(source),
+        quote(target),
+        quote(&Self::label(edge, *valid))
+      ));
+    }
+    lines.push("}".to_string());
+    lines.join("\n") + "\n"
+  }
+
+  fn mermaid(&self) -> String {
+    let quote = |text: &str| format!("\"{}\"", text.replace('"', "#quot;"));
+    let id = |name: &str| {
+      let index = self.packages.iter().position(|package| *package == name);
+      format!("n{}", index.unwrap_or_default())
+    };
+    let mut lines = vec!["graph LR".to_string()];
+    for package in self.packages.iter() {
+      lines.push(format!("  {}[{}]", id(package), quote(package)));
+    }
+    let mut invalid = vec![];
+    for (index, (edge, valid)) in self.edges.iter().enumerate() {
+      let (source, target) = direction(edge, self.dependents);
+      lines.push(format!(
+        "  {} -->|{}| {}",
+        id(source),
+        quote(&Self::label(edge, *valid)),
+        id(target)
+      ));
+      if !valid {
+        invalid.push(index.to_string());
+      }
+    }
+    if !invalid.is_empty() {
+      lines.push(format!(
+        "  linkStyle {} stroke:red,color:red",
+        invalid.join(",")
+      ));
+    }
+    lines.join("\n") + "\n"
+  }
+
+  fn json(&self) -> String {
+    let edges: Vec<_> = self
+      .edges
+      .iter()
+      .map(|(edge, valid)| {
+        json!({
+          "from": edge.from,
+          "to": edge.to,
+          "kind": edge.kind,
+          "range": edge.range,
+          "valid": valid,
+        })
+      })
+      .collect();
+    let graph = json!({
+      "graph": self.title(),
+      "packages": self.packages,
+      "edges": edges,
+    });
+    serde_json::to_string_pretty(&graph).unwrap_or_default() + "\n"
+  }
+}
+
+fn render_graph(graph: &Graph, packages: &Packages) -> Result<String, GraphError> {
+  let dependency_graph = get_dependency_graph(packages, None);
+  let view = GraphView::new(&dependency_graph, packages, graph)?;
+  Ok(match graph.format {
+    GraphFormat::Dot => view.dot(),
+    GraphFormat::Mermaid => view.mermaid(),
+    GraphFormat::Json => view.json(),
+  })
+}
+
+#[derive(Debug, Error)]
+pub enum GraphError {
+  #[error(transparent)]
+  Root(#[from] FindRootError),
+  #[error(transparent)]
+  Config(#[from] ConfigErrors),
+  #[error(transparent)]
+  Packages(#[from] PackagesError),
+  #[error("Package \"{0}\" is not in the workspace")]
+  UnknownPackage(String),
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use fcsr_pkg::{
+    packages::{Package, PackageKind},
+    PkgJson,
+  };
+  use std::collections::HashMap;
+
+  fn packages() -> Packages {
+    let package =
+      |name: &str, dependencies: &[(&str, &str)], peer_dependencies: &[(&str, &str)]| {
+        let section = |entries: &[(&str, &str)]| {
+          (!entries.is_empty()).then(|| {
+            entries
+              .iter()
+              .map(|(name, range)| (name.to_string(), range.to_string()))
+              .collect::<HashMap<_, _>>()
+          })
+        };
+        Package {
+          dir: PathBuf::from(name),
+          package_json: PkgJson {
+            dependencies: section(dependencies),
+            peer_dependencies: section(peer_dependencies),
+            ..PkgJson::new(name.to_string(), "1.0.0".to_string())
+          },
+          kind: PackageKind::Npm,
+        }
+      };
+    Packages {
+      tool: Tool::Pnpm,
+      catalogs: Default::default(),
+      root: package("root", &[], &[]),
+      packages: vec![
+        package("app", &[("@scope/ui", "workspace:^")], &[]),
+        package("@scope/ui", &[("core", "^1.0.0")], &[("core", "^2.0.0")]),
+        package("core", &[], &[]),
+      ],
+    }
+  }
+
+  fn graph(
+    format: GraphFormat,
+    dependents: bool,
+    focus: Option<&str>,
+    depth: Option<usize>,
+  ) -> Graph {
+    Graph {
+      format,
+      dependents,
+      focus: focus.map(String::from),
+      depth,
+    }
+  }
+
+  #[test]
+  fn renders_dot_and_mermaid() {
+    let packages = packages();
+    assert_eq!(
+      render_graph(&graph(GraphFormat::Dot, false, None, None), &packages).unwrap(),
+      r#"digraph dependencies {
+  "@scope/ui";
+  "app";
+  "core";
+  "@scope/ui" -> "core" [label="dependencies"];
+  "app" -> "@scope/ui" [label="dependencies"];
+  "@scope/ui" -> "core" [label="peerDependencies ^2.0.0", color="red", fontcolor="red"];
+}
+"#
+    );
+    assert_eq!(
+      render_graph(
+        &graph(GraphFormat::Mermaid, true, Some("core"), Some(1)),
+        &packages
+      )
+      .unwrap(),
+      r#"graph LR
+  n0["@scope/ui"]
+  n1["core"]
+  n1 -->|"dependencies"| n0
+  n1 -->|"peerDependencies ^2.0.0"| n0
+  linkStyle 1 stroke:red,color:red
+"#
+    );
+  }
+
+  #[test]
+  fn renders_json_neighbourhood() {
+    let packages = packages();
+    let json: serde_json::Value = serde_json::from_str(
+      &render_graph(
+        &graph(GraphFormat::Json, false, Some("app"), Some(1)),
+        &packages,
+      )
+      .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+      json,
+      json!({
+        "graph": "dependencies",
+        "packages": ["@scope/ui", "app"],
+        "edges": [{
+          "from": "app",
+          "to": "@scope/ui",
+          "kind": "dependencies",
+          "range": "workspace:^",
+          "valid": true,
+        }],
+      })
+    );
+    assert!(matches!(
+      render_graph(
+        &graph(GraphFormat::Json, false, Some("nope"), None),
+        &packages
+      ),
+      Err(GraphError::UnknownPackage(_))
+    ));
+  }
+}
@@ -1,23 +1,104 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use fcsr_changeset::changeset_dir;
+use fcsr_config::{
+  legacy::{read_legacy_config, LegacyConfigError, LEGACY_CONFIG_FILE},
+  schema::config_schema,
+  source::ConfigSource,
+  written::{ReadConfigError, WrittenConfig, SCHEMA_FILE},
+};
+use fcsr_diagnostic::{Diagnostic, Diagnostics};
+use fcsr_git::{default_branch, GitError};
 use fcsr_metadata::Init;
+use fcsr_tools::find_root;
 
-pub fn run_init(_: Init, pwd: PathBuf) -> anyhow::Result<(), InitError> {
-  let base = pwd.join(".changeset");
-  let config_base = base.join("config.json");
+const README: &str = r#"# Changesets
 
-  if base.exists() {
-    if !config_base.exists() {
-      if base.join("config.js").exists() {
-        return Err(InitError::PreVersion);
-      } else {
-        std::fs::write(config_base, "123");
+Hello and welcome! This folder has been automatically generated by `fcsr`, a build tool that works
+with multi-package repos, or single-package repos to help you version and publish your code. It reads
+and writes the same files as [`@changesets/cli`](https://github.com/changesets/changesets), so you can
+find the full documentation for it [in the changesets repository](https://github.com/changesets/changesets)
+
+We have a quick list of common questions to get you started engaging with this project in
+[the changesets documentation](https://github.com/changesets/changesets/blob/main/docs/common-questions.md)
+"#;
+
+fn write(path: PathBuf, contents: &str) -> Result<(), InitError> {
+  std::fs::write(&path, contents).map_err(|error| InitError::Io(path, error))
+}
+
+fn write_config(root: &Path, config: WrittenConfig) -> Result<(), InitError> {
+  let base_branch = match config.base_branch {
+    Some(base_branch) => Some(base_branch),
+    None => default_branch(root)?.or(WrittenConfig::default().base_branch),
+  };
+  let config = WrittenConfig {
+    base_branch,
+    ..config
+  };
+  let mut contents = serde_json::to_string_pretty(&config).unwrap_or_default();
+  contents.push('\n');
+  write(ConfigSource::Json.path(root), &contents)
+}
+
+pub fn run_init(
+  Init { force, migrate }: Init,
+  pwd: PathBuf,
+  diagnostics: &mut Diagnostics,
+) -> anyhow::Result<(), InitError> {
+  let root = find_root(pwd.clone()).unwrap_or(pwd);
+  let base = changeset_dir(&root);
+  let legacy = base.join(LEGACY_CONFIG_FILE);
+  if migrate && !legacy.exists() {
+    return Err(InitError::NoLegacyConfig(legacy));
+  }
+  let existed = base.exists();
+  std::fs::create_dir_all(&base).map_err(|error| InitError::Io(base.clone(), error))?;
+
+  let overwrite = match WrittenConfig::read(&root) {
+    Ok(_) if existed && !force => return Err(InitError::BaseHasExist),
+    Ok(_) => migrate,
+    Err(ReadConfigError::NotFound(_)) => {
+      if existed && !migrate {
+        let (code, message) = if legacy.exists() {
+          ("init/pre-version", InitError::PreVersion)
+        } else {
+          ("init/not-have", InitError::NotHave)
+        };
+        diagnostics.push(Diagnostic::warning(code, message.to_string().trim()));
       }
-    } else {
-      return Err(InitError::BaseHasExist);
+      true
     }
+    Err(error) if force && error.path() == Some(&ConfigSource::Json.path(&root)) => true,
+    Err(error) => return Err(InitError::Config(error)),
+  };
+  if overwrite && migrate {
+    let (config, found) = read_legacy_config(&legacy)?;
+    diagnostics.extend(found);
+    write_config(&root, config)?;
+  } else if overwrite {
+    let config = WrittenConfig {
+      base_branch: None,
+      ..WrittenConfig::default()
+    };
+    write_config(&root, config)?;
   }
 
+  if force || !existed {
+    let readme = base.join("README.md");
+    if !readme.exists() {
+      write(readme, README)?;
+    }
+  }
+  let schema = base.join(SCHEMA_FILE);
+  if !schema.exists() || force {
+    let mut contents = serde_json::to_string_pretty(&config_schema()).unwrap_or_default();
+    contents.push('\n');
+    write(schema, &contents)?;
+  }
+
+  println!("Thanks for choosing fcsr to help manage your versioning and publishing.");
+  println!("The config is located at {}", base.display());
   Ok(())
 }
 
@@ -30,6 +111,7 @@ pub enum InitError {
   The format of the config object has significantly changed in v2 as well
   - we thoroughly recommend looking at the changelog for this package for what has changed
   Changesets will write the defaults for the new config, remember to transfer your options into the new config at `.changeset/config.json`
+  or run `fcsr init --migrate` to translate them
 "#)]
   PreVersion,
   #[error(
@@ -39,4 +121,137 @@ pub enum InitError {
 "#
   )]
   NotHave,
+  #[error("{0}. Run `fcsr init --force` to replace it with the default config")]
+  Config(ReadConfigError),
+  #[error("There is no v1 config to migrate at {0}")]
+  NoLegacyConfig(PathBuf),
+  #[error(transparent)]
+  Legacy(#[from] LegacyConfigError),
+  #[error(transparent)]
+  Git(#[from] GitError),
+  #[error("Failed to write {0}: {1}")]
+  Io(PathBuf, std::io::Error),
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use fcsr_test_utils::git;
+
+  fn init(root: &Path, force: bool) -> (Result<(), InitError>, Diagnostics) {
+    let mut diagnostics = Diagnostics::default();
+    let result = run_init(
+      Init {
+        force,
+        migrate: false,
+      },
+      root.to_path_buf(),
+      &mut diagnostics,
+    );
+    (result, diagnostics)
+  }
+
+  #[test]
+  fn writes_default_files() {
+    let origin = tempfile::tempdir().unwrap();
+    git(origin.path(), &["init", "-q", "-b", "develop"]);
+    git(
+      origin.path(),
+      &["commit", "-q", "--allow-empty", "-m", "init"],
+    );
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    git(
+      root,
+      &["clone", "-q", &origin.path().to_string_lossy(), "."],
+    );
+    git(root, &["checkout", "-q", "-b", "feature"]);
+    std::fs::write(root.join("package.json"), r#"{"name":"root"}"#).unwrap();
+
+    let (result, diagnostics) = init(root, false);
+    result.unwrap();
+    assert!(diagnostics.is_empty());
+
+    let config = std::fs::read_to_string(root.join(".changeset/config.json")).unwrap();
+    assert_eq!(
+      config,
+      r#"{
+  "$schema": "./schema.json",
+  "changelog": "@changesets/cli/changelog",
+  "commit": false,
+  "fixed": [],
+  "linked": [],
+  "access": "restricted",
+  "baseBranch": "develop",
+  "updateInternalDependencies": "patch",
+  "ignore": []
+}
+"#
+    );
+    assert!(root.join(".changeset/README.md").exists());
+    assert!(root.join(".changeset/schema.json").exists());
+    assert!(matches!(init(root, false).0, Err(InitError::BaseHasExist)));
+  }
+
+  #[test]
+  fn default_to_master_without_remote_head() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    std::fs::write(root.join("package.json"), r#"{"name":"root"}"#).unwrap();
+    git(root, &["init", "-q", "-b", "develop"]);
+
+    init(root, false).0.unwrap();
+    let config = std::fs::read_to_string(root.join(".changeset/config.json")).unwrap();
+    assert!(config.contains(r#""baseBranch": "master""#), "{config}");
+  }
+
+  #[test]
+  fn repairs_partial_setups() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    std::fs::create_dir_all(root.join(".changeset")).unwrap();
+    std::fs::write(root.join(".changeset/config.js"), "module.exports = {}").unwrap();
+
+    let (result, diagnostics) = init(root, false);
+    result.unwrap();
+    assert_eq!(diagnostics.iter().next().unwrap().code, "init/pre-version");
+    assert!(!root.join(".changeset/README.md").exists());
+
+    std::fs::write(root.join(".changeset/config.json"), "{").unwrap();
+    assert!(matches!(init(root, false).0, Err(InitError::Config(_))));
+    init(root, true).0.unwrap();
+    assert!(WrittenConfig::read(root).is_ok());
+    assert!(root.join(".changeset/README.md").exists());
+  }
+
+  #[test]
+  fn migrates_v1_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    std::fs::create_dir_all(root.join(".changeset")).unwrap();
+    std::fs::write(
+      root.join(".changeset/config.js"),
+      "module.exports = { changesetOptions: { commit: true }, linked: [], prettier: false };\n",
+    )
+    .unwrap();
+
+    let migrate = Init {
+      force: false,
+      migrate: true,
+    };
+    let mut diagnostics = Diagnostics::default();
+    run_init(migrate, root.to_path_buf(), &mut diagnostics).unwrap();
+    let codes: Vec<_> = diagnostics
+      .iter()
+      .map(|diagnostic| diagnostic.code)
+      .collect();
+    assert_eq!(codes, vec!["migrate/untranslatable"]);
+
+    let config = WrittenConfig::read(root).unwrap();
+    assert!(matches!(
+      config.commit,
+      Some(fcsr_config::written::ConfigContainer::Bool(true))
+    ));
+    assert_eq!(config.base_branch.as_deref(), Some("master"));
+  }
 }
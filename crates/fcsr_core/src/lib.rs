@@ -1,3 +1,27 @@
 pub mod add;
+pub mod config;
+pub mod fix_ranges;
+pub mod graph;
 pub mod init;
+pub mod lint;
+pub mod migrate;
+pub mod tag;
 pub mod version;
+
+use fcsr_config::config::PackageSources;
+use fcsr_tools::{PackagesOptions, TagPackageOptions};
+
+pub(crate) fn packages_options(sources: &PackageSources) -> PackagesOptions {
+  PackagesOptions {
+    python: sources.python_packages.clone(),
+    tag: sources
+      .tag_packages
+      .iter()
+      .map(|tag_package| TagPackageOptions {
+        dir: tag_package.dir.clone(),
+        name: tag_package.name.clone(),
+        tag_prefix: tag_package.tag_prefix.clone(),
+      })
+      .collect(),
+  }
+}
@@ -0,0 +1,505 @@
+use std::{
+  collections::{BTreeMap, HashSet},
+  path::PathBuf,
+};
+
+use crate::{
+  fix_ranges::{apply_fixes, RangeFix},
+  packages_options,
+};
+use fcsr_config::{
+  config::DependencyLint,
+  dependency_graph::{get_all_dependencies, DependencyKind, DependencySpec, Protocol},
+  load_config, load_package_sources, ConfigErrors,
+};
+use fcsr_diagnostic::{Diagnostic, Diagnostics};
+use fcsr_metadata::{Lint, LintCommand, LintDeps};
+use fcsr_pkg::{
+  npm_semver::Range,
+  packages::{Package, PackageKind, Packages},
+  pep440::{canonicalize_name, Specifiers},
+  ManifestError,
+};
+use fcsr_tools::{find_root, get_packages, FindRootError, PackagesError};
+use glob::Pattern;
+use semver::Version;
+use thiserror::Error;
+
+pub fn run_lint(lint: Lint, pwd: PathBuf, diagnostics: &mut Diagnostics) -> Result<(), LintError> {
+  match lint.command {
+    LintCommand::Deps(deps) => run_lint_deps(deps, pwd, diagnostics),
+  }
+}
+
+fn run_lint_deps(
+  LintDeps { fix }: LintDeps,
+  pwd: PathBuf,
+  diagnostics: &mut Diagnostics,
+) -> Result<(), LintError> {
+  let root = find_root(pwd)?;
+  let packages = get_packages(&root, &packages_options(&load_package_sources(&root)?))?;
+  let config = load_config(&root, &packages)?;
+  for line in lint_deps(&packages, &config.lint.deps, fix, diagnostics)? {
+    println!("{line}");
+  }
+  Ok(())
+}
+
+struct Declaration<'a> {
+  package: &'a Package,
+  kind: DependencyKind,
+  range: String,
+}
+
+/// Registry dependencies on packages outside the workspace, by name. Peer dependencies are left
+/// out as they are usually meant to be wider than the others.
+fn external_dependencies(
+  packages: &Packages,
+  package_kind: PackageKind,
+) -> BTreeMap<String, Vec<Declaration<'_>>> {
+  let all_packages: Vec<&Package> = packages.packages.iter().chain([&packages.root]).collect();
+  let internal: HashSet<&str> = all_packages
+    .iter()
+    .map(|package| package.package_json.name.as_str())
+    .collect();
+
+  let mut dependencies: BTreeMap<String, Vec<Declaration>> = BTreeMap::new();
+  for package in all_packages
+    .iter()
+    .filter(|package| package.kind == package_kind)
+  {
+    for (kind, name, range) in get_all_dependencies(&package.package_json) {
+      let spec = DependencySpec::parse(&name, &range);
+      if kind == DependencyKind::PeerDependencies
+        || spec.protocol != Protocol::Registry
+        || internal.contains(spec.name)
+        || internal.contains(name.as_str())
+      {
+        continue;
+      }
+      dependencies.entry(name).or_default().push(Declaration {
+        package,
+        kind,
+        range,
+      });
+    }
+  }
+  dependencies
+}
+
+fn lower_bound(package_kind: PackageKind, name: &str, range: &str) -> Option<Version> {
+  if package_kind != PackageKind::Npm {
+    return None;
+  }
+  let range = Range::parse(DependencySpec::parse(name, range).range).ok()?;
+  range.lower_bound().cloned()
+}
+
+/// The range starting at the highest version, or else the most common one.
+fn target_range<'a>(
+  package_kind: PackageKind,
+  name: &str,
+  declarations: &'a [Declaration],
+) -> &'a str {
+  let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+  for declaration in declarations.iter() {
+    *counts.entry(declaration.range.as_str()).or_default() += 1;
+  }
+  counts
+    .into_iter()
+    .max_by_key(|(range, count)| (lower_bound(package_kind, name, range), *count))
+    .map(|(range, _)| range)
+    .unwrap_or_default()
+}
+
+fn is_valid_range(package_kind: PackageKind, name: &str, range: &str) -> bool {
+  match package_kind {
+    PackageKind::Npm => Range::parse(DependencySpec::parse(name, range).range).is_ok(),
+    PackageKind::Python => Specifiers::parse(range).is_ok(),
+    PackageKind::Tag => false,
+  }
+}
+
+/// The range `name` is pinned to in `package_kind` packages. Python names are compared in their
+/// normalized form, and a pin that is not a valid range for the ecosystem is reported and skipped.
+fn pinned_range<'a>(
+  policy: &'a DependencyLint,
+  package_kind: PackageKind,
+  name: &str,
+  diagnostics: &mut Diagnostics,
+) -> Option<&'a str> {
+  let (pinned_name, range) = policy
+    .pin
+    .iter()
+    .find(|(pinned_name, _)| match package_kind {
+      PackageKind::Python => canonicalize_name(pinned_name) == name,
+      _ => *pinned_name == name,
+    })?;
+  if is_valid_range(package_kind, name, range) {
+    return Some(range);
+  }
+  let (packages, expected) = match package_kind {
+    PackageKind::Python => ("Python", "a PEP 440 specifier"),
+    _ => ("npm", "an npm semver range"),
+  };
+  diagnostics.push(
+    Diagnostic::warning(
+      "lint/invalid-pin",
+      format!(
+        r#"Ignored the pin of "{pinned_name}" to "{range}" for {packages} packages as it is not {expected}"#
+      ),
+    )
+    .with_help("Fix the range in the `lint.deps.pin` option"),
+  );
+  None
+}
+
+fn lint_deps(
+  packages: &Packages,
+  policy: &DependencyLint,
+  fix: bool,
+  diagnostics: &mut Diagnostics,
+) -> Result<Vec<String>, LintError> {
+  let mut allowed: Vec<Pattern> = vec![];
+  for pattern in policy.allow.iter() {
+    match Pattern::new(pattern) {
+      Ok(pattern) => allowed.push(pattern),
+      Err(error) => diagnostics.push(
+        Diagnostic::warning(
+          "config/invalid-pattern",
+          format!(
+            r#"Ignored the invalid glob expression "{pattern}" in the `lint.deps.allow` option: {error}"#
+          ),
+        )
+        .with_help(
+          "Glob expressions must be valid according to https://docs.rs/glob/0.3.1/glob/struct.Pattern.html.",
+        ),
+      ),
+    }
+  }
+
+  let mut fixes = vec![];
+  for package_kind in [PackageKind::Npm, PackageKind::Python] {
+    let dependencies = external_dependencies(packages, package_kind);
+    for (name, declarations) in dependencies.iter() {
+      let pinned = pinned_range(policy, package_kind, name, diagnostics);
+      if pinned.is_none() && allowed.iter().any(|pattern| pattern.matches(name)) {
+        continue;
+      }
+      let target = match pinned {
+        Some(pinned) => pinned,
+        None => target_range(package_kind, name, declarations),
+      };
+      let mismatched: Vec<&Declaration> = declarations
+        .iter()
+        .filter(|declaration| declaration.range != target)
+        .collect();
+      if mismatched.is_empty() {
+        continue;
+      }
+
+      if fix {
+        fixes.extend(mismatched.iter().map(|declaration| RangeFix {
+          package: declaration.package,
+          section: declaration.kind.as_str(),
+          name: name.clone(),
+          range: declaration.range.clone(),
+          fixed: target.to_string(),
+        }));
+        continue;
+      }
+
+      if pinned.is_some() {
+        for declaration in mismatched {
+          let mut diagnostic = Diagnostic::error(
+            "lint/unpinned-dependency",
+            format!(
+              r#""{name}" is declared as "{}" in {} but is pinned to "{target}""#,
+              declaration.range,
+              declaration.kind.as_str()
+            ),
+          )
+          .with_package(&declaration.package.package_json.name)
+          .with_help("Run `fcsr lint deps --fix` to rewrite it");
+          if let Some(path) = declaration.package.manifest_path() {
+            diagnostic = diagnostic.with_file(path);
+          }
+          diagnostics.push(diagnostic);
+        }
+        continue;
+      }
+
+      let mut ranges: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+      for declaration in declarations.iter() {
+        let names = ranges.entry(declaration.range.as_str()).or_default();
+        let package_name = declaration.package.package_json.name.as_str();
+        if !names.contains(&package_name) {
+          names.push(package_name);
+        }
+      }
+      let ranges: Vec<String> = ranges
+        .into_iter()
+        .map(|(range, names)| format!(r#""{range}" ({})"#, names.join(", ")))
+        .collect();
+      diagnostics.push(
+        Diagnostic::error(
+          "lint/divergent-dependency",
+          format!(
+            r#""{name}" is declared with {} different ranges: {}"#,
+            ranges.len(),
+            ranges.join(", ")
+          ),
+        )
+        .with_help(format!(
+          r#"Run `fcsr lint deps --fix` to use "{target}" everywhere, or add "{name}" to the `lint.deps.allow` option"#
+        )),
+      );
+    }
+  }
+
+  Ok(apply_fixes(&fixes)?)
+}
+
+#[derive(Debug, Error)]
+pub enum LintError {
+  #[error(transparent)]
+  Root(#[from] FindRootError),
+  #[error(transparent)]
+  Config(#[from] ConfigErrors),
+  #[error(transparent)]
+  Packages(#[from] PackagesError),
+  #[error(transparent)]
+  Manifest(#[from] ManifestError),
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use fcsr_test_utils::workspace;
+
+  #[test]
+  fn aligns_external_ranges() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let app = "{\n  \"name\": \"app\",\n  \"version\": \"1.0.0\",\n  \"dependencies\": { \"react\": \"^18.0.0\", \"lodash\": \"^4.17.0\", \"ui\": \"workspace:*\" },\n  \"devDependencies\": { \"typescript\": \"~5.3.0\" }\n}\n";
+    let ui = "{\n  \"name\": \"ui\",\n  \"version\": \"1.0.0\",\n  \"dependencies\": { \"react\": \"^18.2.0\", \"lodash\": \"4.17.21\" },\n  \"devDependencies\": { \"typescript\": \"~5.4.0\" },\n  \"peerDependencies\": { \"react\": \"^17.0.0 || ^18.0.0\" }\n}\n";
+    let packages = workspace(
+      root,
+      &[("app", PackageKind::Npm, app), ("ui", PackageKind::Npm, ui)],
+    );
+    let policy = DependencyLint {
+      pin: BTreeMap::from([("lodash".to_string(), "4.17.21".to_string())]),
+      allow: vec!["type*".to_string()],
+    };
+
+    let mut diagnostics = Diagnostics::default();
+    assert!(lint_deps(&packages, &policy, false, &mut diagnostics)
+      .unwrap()
+      .is_empty());
+    let messages: Vec<_> = diagnostics
+      .iter()
+      .map(|diagnostic| (diagnostic.code, diagnostic.message.as_str()))
+      .collect();
+    assert_eq!(
+      messages,
+      vec![
+        (
+          "lint/unpinned-dependency",
+          r#""lodash" is declared as "^4.17.0" in dependencies but is pinned to "4.17.21""#
+        ),
+        (
+          "lint/divergent-dependency",
+          r#""react" is declared with 2 different ranges: "^18.0.0" (app), "^18.2.0" (ui)"#
+        ),
+      ]
+    );
+    assert_eq!(
+      std::fs::read_to_string(root.join("app/package.json")).unwrap(),
+      app
+    );
+
+    let mut diagnostics = Diagnostics::default();
+    assert_eq!(
+      lint_deps(&packages, &policy, true, &mut diagnostics).unwrap(),
+      vec![
+        r#"app: dependencies.lodash "^4.17.0" -> "4.17.21""#,
+        r#"app: dependencies.react "^18.0.0" -> "^18.2.0""#,
+      ]
+    );
+    assert!(diagnostics.is_empty());
+    assert_eq!(
+      std::fs::read_to_string(root.join("app/package.json")).unwrap(),
+      app
+        .replace("\"^4.17.0\"", "\"4.17.21\"")
+        .replace("\"^18.0.0\"", "\"^18.2.0\"")
+    );
+    assert_eq!(
+      std::fs::read_to_string(root.join("ui/package.json")).unwrap(),
+      ui
+    );
+  }
+
+  #[test]
+  fn warns_about_invalid_patterns() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let packages = workspace(
+      root,
+      &[
+        (
+          "app",
+          PackageKind::Npm,
+          r#"{"name":"app","version":"1.0.0","dependencies":{"react":"^18.0.0"}}"#,
+        ),
+        (
+          "ui",
+          PackageKind::Npm,
+          r#"{"name":"ui","version":"1.0.0","dependencies":{"react":"^18.2.0"}}"#,
+        ),
+      ],
+    );
+    let policy = DependencyLint {
+      pin: BTreeMap::new(),
+      allow: vec!["[d".to_string(), "react".to_string()],
+    };
+
+    let mut diagnostics = Diagnostics::default();
+    assert!(lint_deps(&packages, &policy, false, &mut diagnostics)
+      .unwrap()
+      .is_empty());
+    let codes: Vec<_> = diagnostics
+      .iter()
+      .map(|diagnostic| diagnostic.code)
+      .collect();
+    assert_eq!(codes, vec!["config/invalid-pattern"]);
+    assert!(!diagnostics.has_errors());
+  }
+
+  #[test]
+  fn aligns_python_ranges_separately() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let worker =
+      "[project]\nname = \"py-worker\"\nversion = \"1.0.0\"\ndependencies = [\"requests>=2.28\"]\n";
+    let packages = workspace(root, &[("py-api", PackageKind::Python, "[project]\nname = \"py-api\"\nversion = \"1.0.0\"\ndependencies = [\"requests>=2.31\", \"pydantic==2.5.0\"]\n"), ("py-worker", PackageKind::Python, worker), ("web", PackageKind::Npm, r#"{"name":"web","version":"1.0.0","dependencies":{"requests":"^1.0.0"}}"#)]);
+    let policy = DependencyLint {
+      pin: BTreeMap::from([("pydantic".to_string(), "==2.5.0".to_string())]),
+      allow: vec![],
+    };
+
+    let mut diagnostics = Diagnostics::default();
+    assert!(lint_deps(&packages, &policy, false, &mut diagnostics)
+      .unwrap()
+      .is_empty());
+    let messages: Vec<_> = diagnostics
+      .iter()
+      .map(|diagnostic| (diagnostic.code, diagnostic.message.as_str()))
+      .collect();
+    assert_eq!(
+      messages,
+      vec![(
+        "lint/divergent-dependency",
+        r#""requests" is declared with 2 different ranges: ">=2.28" (py-worker), ">=2.31" (py-api)"#
+      )]
+    );
+
+    let mut diagnostics = Diagnostics::default();
+    assert_eq!(
+      lint_deps(&packages, &policy, true, &mut diagnostics).unwrap(),
+      vec![r#"py-worker: dependencies.requests ">=2.28" -> ">=2.31""#]
+    );
+    assert!(diagnostics.is_empty());
+    assert_eq!(
+      std::fs::read_to_string(root.join("py-worker/pyproject.toml")).unwrap(),
+      worker.replace(">=2.28", ">=2.31")
+    );
+  }
+
+  #[test]
+  fn ignores_unused_pins() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let app = r#"{"name":"app","version":"1.0.0","dependencies":{"react":"^18.2.0"}}"#;
+    let packages = workspace(root, &[("app", PackageKind::Npm, app)]);
+    let policy = DependencyLint {
+      pin: BTreeMap::from([("left-pad".to_string(), "1.3.0".to_string())]),
+      allow: vec![],
+    };
+
+    for fix in [false, true] {
+      let mut diagnostics = Diagnostics::default();
+      assert!(lint_deps(&packages, &policy, fix, &mut diagnostics)
+        .unwrap()
+        .is_empty());
+      assert!(diagnostics.is_empty());
+    }
+    assert_eq!(
+      std::fs::read_to_string(root.join("app/package.json")).unwrap(),
+      app
+    );
+  }
+
+  #[test]
+  fn checks_pins_per_ecosystem() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let api = "[project]\nname = \"py-api\"\nversion = \"1.0.0\"\ndependencies = [\"Requests>=2.31\", \"pydantic==2.4.0\"]\n";
+    let packages = workspace(
+      root,
+      &[
+        ("py-api", PackageKind::Python, api),
+        (
+          "web",
+          PackageKind::Npm,
+          r#"{"name":"web","version":"1.0.0","dependencies":{"requests":"^2.0.0"}}"#,
+        ),
+      ],
+    );
+    let policy = DependencyLint {
+      pin: BTreeMap::from([
+        ("Pydantic".to_string(), "==2.5.0".to_string()),
+        ("requests".to_string(), "^2.31.0".to_string()),
+      ]),
+      allow: vec![],
+    };
+
+    let mut diagnostics = Diagnostics::default();
+    assert!(lint_deps(&packages, &policy, false, &mut diagnostics)
+      .unwrap()
+      .is_empty());
+    let messages: Vec<_> = diagnostics
+      .iter()
+      .map(|diagnostic| (diagnostic.code, diagnostic.message.as_str()))
+      .collect();
+    assert_eq!(
+      messages,
+      vec![
+        (
+          "lint/unpinned-dependency",
+          r#""requests" is declared as "^2.0.0" in dependencies but is pinned to "^2.31.0""#
+        ),
+        (
+          "lint/unpinned-dependency",
+          r#""pydantic" is declared as "==2.4.0" in dependencies but is pinned to "==2.5.0""#
+        ),
+        (
+          "lint/invalid-pin",
+          r#"Ignored the pin of "requests" to "^2.31.0" for Python packages as it is not a PEP 440 specifier"#
+        ),
+      ]
+    );
+
+    let mut diagnostics = Diagnostics::default();
+    assert_eq!(
+      lint_deps(&packages, &policy, true, &mut diagnostics).unwrap(),
+      vec![
+        r#"web: dependencies.requests "^2.0.0" -> "^2.31.0""#,
+        r#"py-api: dependencies.pydantic "==2.4.0" -> "==2.5.0""#,
+      ]
+    );
+    assert_eq!(
+      std::fs::read_to_string(root.join("py-api/pyproject.toml")).unwrap(),
+      api.replace("==2.4.0", "==2.5.0")
+    );
+  }
+}
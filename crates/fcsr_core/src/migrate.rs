@@ -0,0 +1,31 @@
+use std::path::PathBuf;
+
+use fcsr_changeset::{migrate_legacy_changesets, ReadError};
+use fcsr_metadata::{Migrate, MigrateChangesets, MigrateCommand};
+use fcsr_tools::{find_root, FindRootError};
+use thiserror::Error;
+
+pub fn run_migrate(migrate: Migrate, pwd: PathBuf) -> anyhow::Result<(), MigrateError> {
+  match migrate.command {
+    MigrateCommand::Changesets(changesets) => run_changesets(changesets, pwd),
+  }
+}
+
+fn run_changesets(_: MigrateChangesets, pwd: PathBuf) -> Result<(), MigrateError> {
+  let written = migrate_legacy_changesets(&find_root(pwd)?)?;
+  if written.is_empty() {
+    println!("No v1 changesets found");
+  }
+  for path in written {
+    println!("Migrated {}", path.display());
+  }
+  Ok(())
+}
+
+#[derive(Debug, Error)]
+pub enum MigrateError {
+  #[error(transparent)]
+  Root(#[from] FindRootError),
+  #[error(transparent)]
+  Changesets(#[from] ReadError),
+}
@@ -0,0 +1,233 @@
+use std::path::PathBuf;
+
+use crate::packages_options;
+use fcsr_changeset::{read_pending_versions, write_pending_versions, ReadError};
+use fcsr_config::{config::format_tag, load_config, load_package_sources, ConfigErrors};
+use fcsr_git::{create_tag, tag_exists, GitError};
+use fcsr_metadata::Tag;
+use fcsr_pkg::packages::{Package, PackageKind, Tool};
+use fcsr_tools::{find_root, get_packages, FindRootError, PackagesError};
+use thiserror::Error;
+
+pub fn run_tag(_: Tag, pwd: PathBuf) -> anyhow::Result<(), TagError> {
+  let root = find_root(pwd)?;
+  let sources = load_package_sources(&root)?;
+  let packages = get_packages(&root, &packages_options(&sources))?;
+  let config = load_config(&root, &packages)?;
+  let mut pending = read_pending_versions(&root)?;
+
+  let mut tags = vec![];
+  let mut push_tag = |package: &Package, version: &str, default: String| {
+    let name = package.package_json.name.as_str();
+    let options = config.package_options(name);
+    let private = package.package_json.private == Some(true);
+    if !options.publish || (private && !config.private_packages.tag) {
+      return false;
+    }
+    tags.push(match options.tag_format {
+      Some(format) => format_tag(&format, name, version),
+      None => default,
+    });
+    true
+  };
+  if let Tool::Root = packages.tool {
+    let version = &packages.root.package_json.version;
+    push_tag(&packages.root, version, format!("v{version}"));
+  }
+  for package in packages.packages.iter() {
+    let name = &package.package_json.name;
+    match package.kind {
+      PackageKind::Tag => {
+        if let (Some(version), Some(tag_package)) = (
+          pending.get(name).cloned(),
+          sources
+            .tag_packages
+            .iter()
+            .find(|tag_package| tag_package.name == *name),
+        ) {
+          // Keep the pending version of packages that are not tagged, it is their only record.
+          if push_tag(
+            package,
+            &version,
+            format!("{}{version}", tag_package.tag_prefix),
+          ) {
+            pending.remove(name);
+          }
+        }
+      }
+      _ => {
+        let version = &package.package_json.version;
+        push_tag(package, version, format!("{name}@{version}"));
+      }
+    }
+  }
+
+  for tag in tags {
+    if !tag_exists(&root, &tag)? {
+      create_tag(&root, &tag)?;
+      println!("New tag: {tag}");
+    }
+  }
+  write_pending_versions(&root, &pending)?;
+
+  Ok(())
+}
+
+#[derive(Debug, Error)]
+pub enum TagError {
+  #[error(transparent)]
+  Root(#[from] FindRootError),
+  #[error(transparent)]
+  Config(#[from] ConfigErrors),
+  #[error(transparent)]
+  Packages(#[from] PackagesError),
+  #[error(transparent)]
+  PendingVersions(#[from] ReadError),
+  #[error(transparent)]
+  Git(#[from] GitError),
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::version::run_version;
+  use fcsr_diagnostic::Diagnostics;
+  use fcsr_metadata::Version;
+  use fcsr_test_utils::{git, init_repo, write};
+  use std::path::Path;
+
+  fn version(root: &Path) {
+    let mut diagnostics = Diagnostics::default();
+    run_version(
+      Version { ignore: vec![] },
+      root.to_path_buf(),
+      &mut diagnostics,
+    )
+    .unwrap();
+    assert!(!diagnostics.has_errors());
+  }
+
+  fn tags(root: &Path) -> Vec<String> {
+    git(root, &["tag", "--list"])
+      .lines()
+      .map(String::from)
+      .collect()
+  }
+
+  fn package_version(root: &Path, name: &str) -> String {
+    let sources = load_package_sources(root).unwrap();
+    get_packages(root, &packages_options(&sources))
+      .unwrap()
+      .packages
+      .into_iter()
+      .find(|package| package.package_json.name == name)
+      .unwrap()
+      .package_json
+      .version
+  }
+
+  #[test]
+  fn tag_packages_round_trip() {
+    let repo = init_repo();
+    let root = repo.path();
+    write(
+      root,
+      "package.json",
+      r#"{"name":"root","version":"0.0.0","private":true,"workspaces":["packages/*"]}"#,
+    );
+    write(root, "yarn.lock", "");
+    write(root, "charts/api/Chart.yaml", "name: api\n");
+    write(
+      root,
+      ".changeset/config.json",
+      r#"{"tagPackages":[{"dir":"charts/api"}]}"#,
+    );
+    write(
+      root,
+      ".changeset/one.md",
+      "---\n\"api\": minor\n---\n\nAdd an endpoint\n",
+    );
+    git(root, &["tag", "api@1.2.0"]);
+
+    version(root);
+    assert_eq!(package_version(root, "api"), "1.3.0");
+    run_tag(Tag {}, root.to_path_buf()).unwrap();
+    assert_eq!(tags(root), vec!["api@1.2.0", "api@1.3.0"]);
+    assert!(read_pending_versions(root).unwrap().is_empty());
+    assert_eq!(package_version(root, "api"), "1.3.0");
+  }
+
+  #[test]
+  fn keep_pending_versions_of_untagged_packages() {
+    let repo = init_repo();
+    let root = repo.path();
+    write(
+      root,
+      "package.json",
+      r#"{"name":"root","version":"0.0.0","private":true,"workspaces":["packages/*"]}"#,
+    );
+    write(root, "yarn.lock", "");
+    write(root, "charts/api/Chart.yaml", "name: api\n");
+    write(
+      root,
+      ".changeset/config.json",
+      r#"{"tagPackages":[{"dir":"charts/api"}],"packages":{"api":{"publish":false}}}"#,
+    );
+    write(
+      root,
+      ".changeset/one.md",
+      "---\n\"api\": major\n---\n\nBreak it\n",
+    );
+
+    version(root);
+    run_tag(Tag {}, root.to_path_buf()).unwrap();
+    assert!(tags(root).is_empty());
+    assert_eq!(package_version(root, "api"), "1.0.0");
+  }
+
+  #[test]
+  fn tag_single_package_repos() {
+    let repo = init_repo();
+    let root = repo.path();
+    write(root, "package.json", r#"{"name":"app","version":"1.4.0"}"#);
+    write(root, ".changeset/config.json", "{}");
+
+    run_tag(Tag {}, root.to_path_buf()).unwrap();
+    run_tag(Tag {}, root.to_path_buf()).unwrap();
+    assert_eq!(tags(root), vec!["v1.4.0"]);
+  }
+
+  #[test]
+  fn skip_private_packages() {
+    let repo = init_repo();
+    let root = repo.path();
+    write(
+      root,
+      "package.json",
+      r#"{"name":"root","version":"0.0.0","private":true,"workspaces":["packages/*"]}"#,
+    );
+    write(root, "yarn.lock", "");
+    write(
+      root,
+      "packages/a/package.json",
+      r#"{"name":"a","version":"1.0.0"}"#,
+    );
+    write(
+      root,
+      "packages/example/package.json",
+      r#"{"name":"example","version":"1.0.0","private":true}"#,
+    );
+    write(root, ".changeset/config.json", "{}");
+
+    run_tag(Tag {}, root.to_path_buf()).unwrap();
+    assert_eq!(tags(root), vec!["a@1.0.0"]);
+
+    write(
+      root,
+      ".changeset/config.json",
+      r#"{"privatePackages":{"tag":true}}"#,
+    );
+    run_tag(Tag {}, root.to_path_buf()).unwrap();
+    assert_eq!(tags(root), vec!["a@1.0.0", "example@1.0.0"]);
+  }
+}
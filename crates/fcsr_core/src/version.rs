@@ -1,11 +1,292 @@
 use std::path::PathBuf;
 
+use crate::packages_options;
+use fcsr_changeset::{read_changesets, ReadError};
+use fcsr_config::{
+  config::ConfigContainer, dependency_graph::get_dependency_graph, extend_ignore, load_config,
+  load_package_sources, written::PrivateChangesets, ConfigErrors,
+};
+use fcsr_diagnostic::{Diagnostic, Diagnostics, Severity};
 use fcsr_metadata::Version;
+use fcsr_release::{
+  apply_release_plan, assemble_release_plan, ApplyError, PlanError, ReleaseOptions,
+};
+use fcsr_tools::{find_root, get_packages, FindRootError, PackagesError};
 use thiserror::Error;
 
-pub fn run_version(command: Version, pwd: PathBuf) -> anyhow::Result<(), VersionError> {
+const PRIVATE_HELP: &str =
+  "Private packages are not versioned because `privatePackages.version` is false";
+
+pub fn run_version(
+  Version { ignore }: Version,
+  pwd: PathBuf,
+  diagnostics: &mut Diagnostics,
+) -> anyhow::Result<(), VersionError> {
+  let root = find_root(pwd)?;
+  let packages = get_packages(&root, &packages_options(&load_package_sources(&root)?))?;
+  let mut config = load_config(&root, &packages)?;
+  extend_ignore(&mut config, &ignore, &packages)?;
+  // Versioning still runs with mismatched ranges, so they must not fail the command.
+  diagnostics.extend(
+    get_dependency_graph(&packages, None)
+      .diagnostics
+      .into_iter()
+      .map(|diagnostic| Diagnostic {
+        severity: Severity::Warning,
+        ..diagnostic
+      }),
+  );
+
+  let changesets = read_changesets(&root)?;
+  if changesets.is_empty() {
+    return Err(VersionError::NoChangesets);
+  }
+
+  let skip_private = !config.private_packages.version;
+  let reject_private =
+    skip_private && config.private_packages.changesets == PrivateChangesets::Reject;
+  let is_private = |name: &str| {
+    packages
+      .packages
+      .iter()
+      .chain([&packages.root])
+      .any(|package| {
+        package.package_json.name == name && package.package_json.private == Some(true)
+      })
+  };
+  let is_ignored = |name: &str| config.ignore.iter().any(|ignored| ignored == name);
+
+  let mut rejected = false;
+  for changeset in changesets.iter() {
+    let private = changeset
+      .releases
+      .iter()
+      .filter(|release| skip_private && is_private(&release.name));
+    for release in private {
+      let diagnostic = match reject_private {
+        true => {
+          rejected = true;
+          Diagnostic::error(
+            "version/private-package",
+            format!(
+              "Changeset {} releases private package \"{}\"",
+              changeset.id, release.name
+            ),
+          )
+          .with_help(
+            r#"Remove it from the changeset or set `privatePackages.changesets` to "ignore""#,
+          )
+        }
+        false => Diagnostic::warning(
+          "version/private-package",
+          format!(
+            "Skipped the release of private package \"{}\" in changeset {}",
+            release.name, changeset.id
+          ),
+        )
+        .with_help(PRIVATE_HELP),
+      };
+      diagnostics.push(diagnostic.with_package(&release.name));
+    }
+
+    if reject_private
+      || changeset.releases.is_empty()
+      || !changeset
+        .releases
+        .iter()
+        .all(|release| is_ignored(&release.name) || (skip_private && is_private(&release.name)))
+    {
+      continue;
+    }
+    let diagnostic = match changeset
+      .releases
+      .iter()
+      .all(|release| is_ignored(&release.name))
+    {
+      true => Diagnostic::warning(
+        "version/ignored-changeset",
+        format!(
+          "Left changeset {} untouched because it only releases ignored packages",
+          changeset.id
+        ),
+      )
+      .with_help("Remove the packages from `ignore` to release it"),
+      false => Diagnostic::warning(
+        "version/private-changeset",
+        format!(
+          "Left changeset {} untouched because it only releases private or ignored packages",
+          changeset.id
+        ),
+      )
+      .with_help("It will be released once `privatePackages.version` is true"),
+    };
+    diagnostics.push(diagnostic);
+  }
+  if rejected {
+    return Ok(());
+  }
+
+  let options = ReleaseOptions {
+    changelog: config.changelog != ConfigContainer::Bool(false),
+    changelog_paths: packages
+      .packages
+      .iter()
+      .chain([&packages.root])
+      .map(|package| {
+        let name = &package.package_json.name;
+        (name.clone(), config.package_options(name).changelog)
+      })
+      .collect(),
+    update_internal_dependencies: config.update_internal_dependencies,
+    fixed: config.fixed,
+    linked: config.linked,
+    private_packages: config.private_packages,
+    ignore: config.ignore,
+  };
+  let plan = assemble_release_plan(&changesets, &packages, &options)?;
+  apply_release_plan(&plan, &packages, &root, &options)?;
+
   Ok(())
 }
 
 #[derive(Debug, Error)]
-pub enum VersionError {}
+pub enum VersionError {
+  #[error(transparent)]
+  Root(#[from] FindRootError),
+  #[error(transparent)]
+  Config(#[from] ConfigErrors),
+  #[error(transparent)]
+  Packages(#[from] PackagesError),
+  #[error(transparent)]
+  Changesets(#[from] ReadError),
+  #[error(transparent)]
+  Plan(#[from] PlanError),
+  #[error(transparent)]
+  Apply(#[from] ApplyError),
+  #[error("No unreleased changesets found, exiting.")]
+  NoChangesets,
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use fcsr_test_utils::write;
+  use std::path::Path;
+
+  fn version(root: &Path) -> Diagnostics {
+    let mut diagnostics = Diagnostics::default();
+    run_version(
+      Version { ignore: vec![] },
+      root.to_path_buf(),
+      &mut diagnostics,
+    )
+    .unwrap();
+    diagnostics
+  }
+
+  fn codes(diagnostics: &Diagnostics) -> Vec<&str> {
+    diagnostics
+      .iter()
+      .map(|diagnostic| diagnostic.code)
+      .collect()
+  }
+
+  #[test]
+  fn handles_private_changesets() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(
+      root,
+      "package.json",
+      r#"{"name":"root","version":"0.0.0","private":true,"workspaces":["packages/*"]}"#,
+    );
+    write(root, "yarn.lock", "");
+    write(
+      root,
+      "packages/a/package.json",
+      r#"{"name":"a","version":"1.0.0"}"#,
+    );
+    write(
+      root,
+      "packages/example/package.json",
+      r#"{"name":"example","version":"1.0.0","private":true}"#,
+    );
+    write(
+      root,
+      ".changeset/one.md",
+      "---\n\"a\": minor\n---\n\nAdd a\n",
+    );
+    write(
+      root,
+      ".changeset/two.md",
+      "---\n\"example\": minor\n---\n\nAdd an example\n",
+    );
+
+    write(
+      root,
+      ".changeset/config.json",
+      r#"{"privatePackages":{"version":false,"changesets":"reject"}}"#,
+    );
+    let diagnostics = version(root);
+    assert_eq!(codes(&diagnostics), vec!["version/private-package"]);
+    assert!(diagnostics.has_errors());
+    assert!(root.join(".changeset/one.md").exists());
+    assert!(!root.join("packages/a/CHANGELOG.md").exists());
+
+    write(
+      root,
+      ".changeset/config.json",
+      r#"{"privatePackages":{"version":false}}"#,
+    );
+    let diagnostics = version(root);
+    assert_eq!(
+      codes(&diagnostics),
+      vec!["version/private-package", "version/private-changeset"]
+    );
+    assert!(!diagnostics.has_errors());
+    assert!(!root.join(".changeset/one.md").exists());
+    assert!(root.join(".changeset/two.md").exists());
+    assert!(
+      std::fs::read_to_string(root.join("packages/a/package.json"))
+        .unwrap()
+        .contains("1.1.0")
+    );
+  }
+
+  #[test]
+  fn warn_about_mismatched_ranges() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(
+      root,
+      "package.json",
+      r#"{"name":"root","version":"0.0.0","private":true,"workspaces":["packages/*"]}"#,
+    );
+    write(root, "yarn.lock", "");
+    write(root, ".changeset/config.json", "{}");
+    write(
+      root,
+      "packages/a/package.json",
+      r#"{"name":"a","version":"2.0.0"}"#,
+    );
+    write(
+      root,
+      "packages/b/package.json",
+      r#"{"name":"b","version":"1.0.0","dependencies":{"a":"^1.0.0"}}"#,
+    );
+    write(
+      root,
+      ".changeset/one.md",
+      "---\n\"b\": patch\n---\n\nFix b\n",
+    );
+
+    let diagnostics = version(root);
+    assert_eq!(codes(&diagnostics), vec!["graph/mismatched-range"]);
+    assert!(!diagnostics.has_errors());
+    assert!(
+      std::fs::read_to_string(root.join("packages/b/package.json"))
+        .unwrap()
+        .contains("1.0.1")
+    );
+  }
+}
@@ -0,0 +1,114 @@
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+  Warning,
+  Error,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+  pub code: &'static str,
+  pub severity: Severity,
+  pub message: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub package: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub file: Option<PathBuf>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub help: Option<String>,
+}
+
+impl Diagnostic {
+  pub fn new(code: &'static str, severity: Severity, message: impl Into<String>) -> Self {
+    Self {
+      code,
+      severity,
+      message: message.into(),
+      package: None,
+      file: None,
+      help: None,
+    }
+  }
+
+  pub fn error(code: &'static str, message: impl Into<String>) -> Self {
+    Self::new(code, Severity::Error, message)
+  }
+
+  pub fn warning(code: &'static str, message: impl Into<String>) -> Self {
+    Self::new(code, Severity::Warning, message)
+  }
+
+  pub fn with_package(self, package: impl Into<String>) -> Self {
+    Self {
+      package: Some(package.into()),
+      ..self
+    }
+  }
+
+  pub fn with_file(self, file: impl Into<PathBuf>) -> Self {
+    Self {
+      file: Some(file.into()),
+      ..self
+    }
+  }
+
+  pub fn with_help(self, help: impl Into<String>) -> Self {
+    Self {
+      help: Some(help.into()),
+      ..self
+    }
+  }
+}
+
+#[derive(Debug, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct Diagnostics(Vec<Diagnostic>);
+
+impl Diagnostics {
+  pub fn push(&mut self, diagnostic: Diagnostic) {
+    self.0.push(diagnostic);
+  }
+
+  pub fn iter(&self) -> std::slice::Iter<'_, Diagnostic> {
+    self.0.iter()
+  }
+
+  pub fn len(&self) -> usize {
+    self.0.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+
+  pub fn has_errors(&self) -> bool {
+    self
+      .0
+      .iter()
+      .any(|diagnostic| diagnostic.severity == Severity::Error)
+  }
+}
+
+impl Extend<Diagnostic> for Diagnostics {
+  fn extend<T: IntoIterator<Item = Diagnostic>>(&mut self, iter: T) {
+    self.0.extend(iter);
+  }
+}
+
+impl FromIterator<Diagnostic> for Diagnostics {
+  fn from_iter<T: IntoIterator<Item = Diagnostic>>(iter: T) -> Self {
+    Self(iter.into_iter().collect())
+  }
+}
+
+impl IntoIterator for Diagnostics {
+  type Item = Diagnostic;
+  type IntoIter = std::vec::IntoIter<Diagnostic>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.0.into_iter()
+  }
+}
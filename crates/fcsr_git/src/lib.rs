@@ -1,14 +1,99 @@
-pub fn add(left: usize, right: usize) -> usize {
-  left + right
+use std::{path::Path, process::Command};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum GitError {
+  #[error("Failed to run git: {0}")]
+  Spawn(#[from] std::io::Error),
+  #[error("`git {0}` failed: {1}")]
+  Command(String, String),
+}
+
+fn git(cwd: &Path, args: &[&str]) -> Result<String, GitError> {
+  let output = Command::new("git").args(args).current_dir(cwd).output()?;
+  if !output.status.success() {
+    return Err(GitError::Command(
+      args.join(" "),
+      String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    ));
+  }
+  Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+pub fn get_tags(cwd: &Path, prefix: &str) -> Result<Vec<String>, GitError> {
+  let pattern = format!("{prefix}*");
+  Ok(
+    git(cwd, &["tag", "--list", &pattern])?
+      .lines()
+      .map(str::trim)
+      .filter(|tag| !tag.is_empty())
+      .map(String::from)
+      .collect(),
+  )
+}
+
+pub fn tag_exists(cwd: &Path, tag: &str) -> Result<bool, GitError> {
+  Ok(get_tags(cwd, tag)?.iter().any(|existing| existing == tag))
+}
+
+pub fn create_tag(cwd: &Path, tag: &str) -> Result<(), GitError> {
+  git(cwd, &["tag", tag])?;
+  Ok(())
+}
+
+/// The branch `origin/HEAD` points to, `None` without a remote HEAD or outside a repository.
+pub fn default_branch(cwd: &Path) -> Result<Option<String>, GitError> {
+  match git(
+    cwd,
+    &["symbolic-ref", "--short", "refs/remotes/origin/HEAD"],
+  ) {
+    Ok(remote_head) => Ok(remote_head.trim().strip_prefix("origin/").map(String::from)),
+    Err(GitError::Command(..)) => Ok(None),
+    Err(error) => Err(error),
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use fcsr_test_utils::init_repo;
 
   #[test]
-  fn it_works() {
-    let result = add(2, 2);
-    assert_eq!(result, 4);
+  fn create_and_list_tags() {
+    let repo = init_repo();
+    create_tag(repo.path(), "charts/api/v1.0.0").unwrap();
+    create_tag(repo.path(), "charts/api/v1.2.0").unwrap();
+    create_tag(repo.path(), "other@1.0.0").unwrap();
+
+    let mut tags = get_tags(repo.path(), "charts/api/v").unwrap();
+    tags.sort();
+    assert_eq!(tags, vec!["charts/api/v1.0.0", "charts/api/v1.2.0"]);
+    assert!(tag_exists(repo.path(), "other@1.0.0").unwrap());
+    assert!(!tag_exists(repo.path(), "other@1.0").unwrap());
+    assert!(matches!(
+      create_tag(repo.path(), "other@1.0.0"),
+      Err(GitError::Command(..))
+    ));
+  }
+
+  #[test]
+  fn detect_default_branch() {
+    let repo = init_repo();
+    assert_eq!(default_branch(repo.path()).unwrap(), None);
+
+    let clone = tempfile::tempdir().unwrap();
+    git(
+      clone.path(),
+      &["clone", "-q", &repo.path().to_string_lossy(), "."],
+    )
+    .unwrap();
+    git(clone.path(), &["checkout", "-q", "-b", "feature"]).unwrap();
+    assert_eq!(
+      default_branch(clone.path()).unwrap().as_deref(),
+      Some("trunk")
+    );
+
+    let not_a_repo = tempfile::tempdir().unwrap();
+    assert_eq!(default_branch(not_a_repo.path()).unwrap(), None);
   }
 }
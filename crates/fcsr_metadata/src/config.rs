@@ -0,0 +1,41 @@
+use clap::{Parser, Subcommand};
+
+#[derive(Debug, Parser)]
+pub struct Config {
+  #[command(subcommand)]
+  pub command: ConfigCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+  /// Print the JSON Schema describing `.changeset/config.json`
+  Schema(ConfigSchema),
+  /// Print the value at a path of the config file, e.g. `snapshot.prereleaseTemplate`
+  Get(ConfigGet),
+  /// Set the value at a path of the config file, keeping its formatting
+  Set(ConfigSet),
+  /// Print the effective config with every default filled in
+  List(ConfigList),
+}
+
+#[derive(Debug, Parser)]
+pub struct ConfigSchema {
+  /// Write the schema next to the config instead of printing it
+  #[arg(long)]
+  pub write: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct ConfigGet {
+  pub path: String,
+}
+
+#[derive(Debug, Parser)]
+pub struct ConfigSet {
+  pub path: String,
+  /// A JSON value, anything that isn't valid JSON is set as a string
+  pub value: String,
+}
+
+#[derive(Debug, Parser)]
+pub struct ConfigList {}
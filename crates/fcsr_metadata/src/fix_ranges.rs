@@ -0,0 +1,8 @@
+use clap::Parser;
+
+#[derive(Debug, Parser)]
+pub struct FixRanges {
+  /// Report mismatched ranges without rewriting them, failing if there are any
+  #[arg(long)]
+  pub check: bool,
+}
@@ -0,0 +1,23 @@
+use clap::{Parser, ValueEnum};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GraphFormat {
+  Dot,
+  Mermaid,
+  Json,
+}
+
+#[derive(Debug, Parser)]
+pub struct Graph {
+  #[arg(long, value_enum, default_value_t = GraphFormat::Dot)]
+  pub format: GraphFormat,
+  /// Point edges from each package to its dependents instead of its dependencies
+  #[arg(long)]
+  pub dependents: bool,
+  /// Only show the packages reachable from this one
+  #[arg(long)]
+  pub focus: Option<String>,
+  /// How many edges to follow from `--focus`
+  #[arg(long, requires = "focus")]
+  pub depth: Option<usize>,
+}
@@ -1,4 +1,11 @@
 use clap::Parser;
 
 #[derive(Debug, Parser)]
-pub struct Init;
+pub struct Init {
+  /// Recreate missing or broken files of an existing setup
+  #[arg(long)]
+  pub force: bool,
+  /// Translate a v1 `.changeset/config.js` into `config.json`
+  #[arg(long)]
+  pub migrate: bool,
+}
@@ -1,7 +1,19 @@
 mod add;
+mod config;
+mod fix_ranges;
+mod graph;
 mod init;
+mod lint;
+mod migrate;
+mod tag;
 mod version;
 
 pub use add::Add;
+pub use config::{Config, ConfigCommand, ConfigGet, ConfigList, ConfigSchema, ConfigSet};
+pub use fix_ranges::FixRanges;
+pub use graph::{Graph, GraphFormat};
 pub use init::Init;
+pub use lint::{Lint, LintCommand, LintDeps};
+pub use migrate::{Migrate, MigrateChangesets, MigrateCommand};
+pub use tag::Tag;
 pub use version::Version;
@@ -0,0 +1,20 @@
+use clap::{Parser, Subcommand};
+
+#[derive(Debug, Parser)]
+pub struct Lint {
+  #[command(subcommand)]
+  pub command: LintCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum LintCommand {
+  /// Find external dependencies declared with different ranges across packages
+  Deps(LintDeps),
+}
+
+#[derive(Debug, Parser)]
+pub struct LintDeps {
+  /// Rewrite every range to the pinned one, or else the highest one declared
+  #[arg(long)]
+  pub fix: bool,
+}
@@ -0,0 +1,16 @@
+use clap::{Parser, Subcommand};
+
+#[derive(Debug, Parser)]
+pub struct Migrate {
+  #[command(subcommand)]
+  pub command: MigrateCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum MigrateCommand {
+  /// Convert v1 changeset directories into markdown changesets
+  Changesets(MigrateChangesets),
+}
+
+#[derive(Debug, Parser)]
+pub struct MigrateChangesets {}
@@ -0,0 +1,4 @@
+use clap::Parser;
+
+#[derive(Debug, Parser)]
+pub struct Tag {}
@@ -1,4 +1,8 @@
 use clap::Parser;
 
 #[derive(Debug, Parser)]
-pub struct Version {}
+pub struct Version {
+  /// Hold back packages matching this name or glob for this release, can be repeated
+  #[arg(long)]
+  pub ignore: Vec<String>,
+}
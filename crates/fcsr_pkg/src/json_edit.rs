@@ -0,0 +1,417 @@
+use serde_json::{Map, Value};
+use std::{fmt::Display, ops::Range};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum JsonEditError {
+  #[error("Unexpected {0} at byte {1}")]
+  Unexpected(String, usize),
+  #[error("\"{0}\" is not an object")]
+  NotAnObject(String),
+  #[error("\"{0}\" has no element {1}")]
+  NoElement(String, String),
+}
+
+#[derive(Debug)]
+struct Member {
+  key: String,
+  value: Node,
+}
+
+#[derive(Debug)]
+enum NodeKind {
+  Object(Vec<Member>),
+  Array(Vec<Node>),
+  Scalar(Value),
+}
+
+#[derive(Debug)]
+struct Node {
+  span: Range<usize>,
+  kind: NodeKind,
+}
+
+impl Node {
+  fn to_value(&self) -> Value {
+    match &self.kind {
+      NodeKind::Object(members) => Value::Object(
+        members
+          .iter()
+          .map(|member| (member.key.clone(), member.value.to_value()))
+          .collect(),
+      ),
+      NodeKind::Array(items) => Value::Array(items.iter().map(Node::to_value).collect()),
+      NodeKind::Scalar(value) => value.clone(),
+    }
+  }
+}
+
+struct Parser<'a> {
+  source: &'a str,
+  pos: usize,
+}
+
+impl<'a> Parser<'a> {
+  fn peek(&self) -> Option<u8> {
+    self.source.as_bytes().get(self.pos).copied()
+  }
+
+  fn error_at(&self, pos: usize) -> JsonEditError {
+    match self.source[pos..].chars().next() {
+      Some(char) => JsonEditError::Unexpected(format!("'{char}'"), pos),
+      None => JsonEditError::Unexpected(String::from("end of input"), pos),
+    }
+  }
+
+  fn skip_trivia(&mut self) {
+    loop {
+      let rest = &self.source[self.pos..];
+      let trimmed = rest.trim_start();
+      self.pos += rest.len() - trimmed.len();
+      if trimmed.starts_with("//") {
+        self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+      } else if trimmed.starts_with("/*") {
+        self.pos += trimmed.find("*/").map_or(trimmed.len(), |end| end + 2);
+      } else {
+        break;
+      }
+    }
+  }
+
+  fn expect(&mut self, byte: u8) -> Result<(), JsonEditError> {
+    self.skip_trivia();
+    if self.peek() != Some(byte) {
+      return Err(self.error_at(self.pos));
+    }
+    self.pos += 1;
+    Ok(())
+  }
+
+  fn string(&mut self) -> Result<String, JsonEditError> {
+    let start = self.pos;
+    self.pos += 1;
+    loop {
+      match self.peek() {
+        Some(b'\\') => self.pos += 2,
+        Some(b'"') => break,
+        Some(_) => self.pos += 1,
+        None => return Err(self.error_at(self.source.len())),
+      }
+    }
+    self.pos += 1;
+    serde_json::from_str(&self.source[start..self.pos]).map_err(|_| self.error_at(start))
+  }
+
+  fn value(&mut self) -> Result<Node, JsonEditError> {
+    self.skip_trivia();
+    let start = self.pos;
+    let kind = match self.peek() {
+      Some(b'{') => {
+        self.pos += 1;
+        let mut members = vec![];
+        loop {
+          self.skip_trivia();
+          match self.peek() {
+            Some(b'}') => break,
+            Some(b'"') => {}
+            _ => return Err(self.error_at(self.pos)),
+          }
+          let key = self.string()?;
+          self.expect(b':')?;
+          members.push(Member {
+            key,
+            value: self.value()?,
+          });
+          self.skip_trivia();
+          match self.peek() {
+            Some(b',') => self.pos += 1,
+            Some(b'}') => break,
+            _ => return Err(self.error_at(self.pos)),
+          }
+        }
+        self.pos += 1;
+        NodeKind::Object(members)
+      }
+      Some(b'[') => {
+        self.pos += 1;
+        let mut items = vec![];
+        loop {
+          self.skip_trivia();
+          if self.peek() == Some(b']') {
+            break;
+          }
+          items.push(self.value()?);
+          self.skip_trivia();
+          match self.peek() {
+            Some(b',') => self.pos += 1,
+            Some(b']') => break,
+            _ => return Err(self.error_at(self.pos)),
+          }
+        }
+        self.pos += 1;
+        NodeKind::Array(items)
+      }
+      Some(b'"') => NodeKind::Scalar(Value::String(self.string()?)),
+      _ => {
+        while matches!(self.peek(), Some(byte) if byte.is_ascii_alphanumeric() || b"+-.".contains(&byte))
+        {
+          self.pos += 1;
+        }
+        NodeKind::Scalar(
+          serde_json::from_str(&self.source[start..self.pos]).map_err(|_| self.error_at(start))?,
+        )
+      }
+    };
+    Ok(Node {
+      span: start..self.pos,
+      kind,
+    })
+  }
+}
+
+struct Style {
+  indent: String,
+  newline: &'static str,
+}
+
+impl Style {
+  fn detect(source: &str) -> Self {
+    let indent = source
+      .lines()
+      .skip(1)
+      .map(|line| &line[..line.len() - line.trim_start().len()])
+      .find(|indent| !indent.is_empty())
+      .unwrap_or("  ");
+    Self {
+      indent: indent.to_string(),
+      newline: if source.contains("\r\n") {
+        "\r\n"
+      } else {
+        "\n"
+      },
+    }
+  }
+
+  fn render(&self, value: &Value, depth: usize) -> String {
+    let (open, close, entries): (&str, &str, Vec<String>) = match value {
+      Value::Object(map) if !map.is_empty() => (
+        "{",
+        "}",
+        map
+          .iter()
+          .map(|(key, value)| self.member(key, value, depth + 1))
+          .collect(),
+      ),
+      Value::Array(items) if !items.is_empty() => (
+        "[",
+        "]",
+        items
+          .iter()
+          .map(|item| {
+            format!(
+              "{}{}",
+              self.indent.repeat(depth + 1),
+              self.render(item, depth + 1)
+            )
+          })
+          .collect(),
+      ),
+      _ => return value.to_string(),
+    };
+    format!(
+      "{open}{newline}{}{newline}{}{close}",
+      entries.join(&format!(",{}", self.newline)),
+      self.indent.repeat(depth),
+      newline = self.newline
+    )
+  }
+
+  fn member(&self, key: &str, value: &Value, depth: usize) -> String {
+    format!(
+      "{}{}: {}",
+      self.indent.repeat(depth),
+      Value::from(key),
+      self.render(value, depth)
+    )
+  }
+}
+
+#[derive(Debug)]
+pub struct JsonDocument {
+  source: String,
+  root: Node,
+}
+
+impl JsonDocument {
+  pub fn parse(source: &str) -> Result<Self, JsonEditError> {
+    let mut parser = Parser { source, pos: 0 };
+    let root = parser.value()?;
+    parser.skip_trivia();
+    if parser.pos != source.len() {
+      return Err(parser.error_at(parser.pos));
+    }
+    Ok(Self {
+      source: source.to_string(),
+      root,
+    })
+  }
+
+  fn node(&self, path: &[&str]) -> Option<&Node> {
+    path
+      .iter()
+      .try_fold(&self.root, |node, key| match &node.kind {
+        NodeKind::Object(members) => members
+          .iter()
+          .rev()
+          .find(|member| member.key == *key)
+          .map(|member| &member.value),
+        NodeKind::Array(items) => key.parse().ok().and_then(|index: usize| items.get(index)),
+        NodeKind::Scalar(_) => None,
+      })
+  }
+
+  pub fn get(&self, path: &[&str]) -> Option<Value> {
+    self.node(path).map(Node::to_value)
+  }
+
+  pub fn to_value(&self) -> Value {
+    self.root.to_value()
+  }
+
+  pub fn set(&mut self, path: &[&str], value: &Value) -> Result<(), JsonEditError> {
+    let style = Style::detect(&self.source);
+    let mut node = &self.root;
+    for (depth, key) in path.iter().enumerate() {
+      let members = match &node.kind {
+        NodeKind::Object(members) => members,
+        NodeKind::Array(items) => {
+          node = key
+            .parse()
+            .ok()
+            .and_then(|index: usize| items.get(index))
+            .ok_or_else(|| JsonEditError::NoElement(path[..depth].join("."), key.to_string()))?;
+          continue;
+        }
+        NodeKind::Scalar(_) => return Err(JsonEditError::NotAnObject(path[..depth].join("."))),
+      };
+      match members.iter().rev().find(|member| member.key == *key) {
+        Some(member) => node = &member.value,
+        None => {
+          let value = path[depth + 1..]
+            .iter()
+            .rev()
+            .fold(value.clone(), |value, key| {
+              Value::Object(Map::from_iter([(key.to_string(), value)]))
+            });
+          let (range, replacement) = match members.last() {
+            Some(last) if self.source[node.span.clone()].contains('\n') => (
+              last.value.span.end..last.value.span.end,
+              format!(",{}{}", style.newline, style.member(key, &value, depth + 1)),
+            ),
+            Some(last) => (
+              last.value.span.end..last.value.span.end,
+              format!(", {}: {value}", Value::from(*key)),
+            ),
+            None => {
+              let object = Value::Object(Map::from_iter([(key.to_string(), value)]));
+              (node.span.clone(), style.render(&object, depth))
+            }
+          };
+          return self.replace(range, &replacement);
+        }
+      }
+    }
+
+    let replacement = style.render(value, path.len());
+    self.replace(node.span.clone(), &replacement)
+  }
+
+  fn replace(&mut self, range: Range<usize>, replacement: &str) -> Result<(), JsonEditError> {
+    let mut source = self.source.clone();
+    source.replace_range(range, replacement);
+    *self = Self::parse(&source)?;
+    Ok(())
+  }
+}
+
+impl Display for JsonDocument {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(&self.source)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use serde_json::json;
+
+  #[test]
+  fn edits_values_in_place() {
+    let source = "{\n    \"name\": \"a\",\n    \"version\": \"1.0.0\",\n    \"scripts\": {\"build\": \"tsc\"},\n    \"dependencies\": {\n        \"b\": \"^1.0.0\"\n    }\n}";
+    let mut document = JsonDocument::parse(source).unwrap();
+    document.set(&["version"], &json!("1.1.0")).unwrap();
+    document
+      .set(&["dependencies", "b"], &json!("^2.0.0"))
+      .unwrap();
+
+    assert_eq!(
+      document.to_string(),
+      source
+        .replace("^1.0.0", "^2.0.0")
+        .replace("\"1.0.0\"", "\"1.1.0\"")
+    );
+    assert_eq!(document.get(&["scripts", "build"]), Some(json!("tsc")));
+  }
+
+  #[test]
+  fn edits_array_elements() {
+    let source = "{\"fixed\": [[\"a\", \"b\"], [\"c\"]]}";
+    let mut document = JsonDocument::parse(source).unwrap();
+    document.set(&["fixed", "1", "0"], &json!("d")).unwrap();
+    assert_eq!(document.to_string(), source.replace("\"c\"", "\"d\""));
+    assert_eq!(document.get(&["fixed", "0", "1"]), Some(json!("b")));
+    assert_eq!(
+      document.set(&["fixed", "2"], &json!([])).unwrap_err(),
+      JsonEditError::NoElement(String::from("fixed"), String::from("2"))
+    );
+  }
+
+  #[test]
+  fn inserts_with_detected_style() {
+    let source = "{\r\n\t\"name\": \"a\",\r\n\t\"nested\": {}\r\n}\r\n";
+    let mut document = JsonDocument::parse(source).unwrap();
+    document.set(&["version"], &json!("1.0.0")).unwrap();
+    document.set(&["nested", "a", "b"], &json!([1])).unwrap();
+
+    assert_eq!(
+      document.to_string(),
+      "{\r\n\t\"name\": \"a\",\r\n\t\"nested\": {\r\n\t\t\"a\": {\r\n\t\t\t\"b\": [\r\n\t\t\t\t1\r\n\t\t\t]\r\n\t\t}\r\n\t},\r\n\t\"version\": \"1.0.0\"\r\n}\r\n"
+    );
+  }
+
+  #[test]
+  fn accepts_comments_and_trailing_commas() {
+    let source = "{\n  // base branch\n  \"baseBranch\": \"main\", /* inline */\n  \"fixed\": [[\"a\",],],\n}\n";
+    let mut document = JsonDocument::parse(source).unwrap();
+    assert_eq!(
+      document.to_value(),
+      json!({"baseBranch": "main", "fixed": [["a"]]})
+    );
+
+    document.set(&["baseBranch"], &json!("next")).unwrap();
+    assert_eq!(document.to_string(), source.replace("main", "next"));
+  }
+
+  #[test]
+  fn reports_errors() {
+    assert_eq!(
+      JsonDocument::parse("{\"a\": 1 \"b\"}").unwrap_err(),
+      JsonEditError::Unexpected(String::from("'\"'"), 8)
+    );
+    let mut document = JsonDocument::parse("{\"a\": 1}").unwrap();
+    assert_eq!(
+      document.set(&["a", "b"], &json!(1)).unwrap_err(),
+      JsonEditError::NotAnObject(String::from("a"))
+    );
+  }
+}
@@ -1,4 +1,10 @@
+pub mod json_edit;
+mod manifest;
+pub mod npm_semver;
 pub mod packages;
+pub mod pep440;
 mod pkg_json;
+pub mod pyproject;
 
+pub use manifest::*;
 pub use pkg_json::*;
@@ -0,0 +1,122 @@
+use crate::{
+  json_edit::{JsonDocument, JsonEditError},
+  packages::{Package, PackageKind},
+  pyproject::{PyProject, PyProjectError},
+  PkgJson,
+};
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Rewrites the range of `name` in `section` from `from` to `to`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeUpdate<'a> {
+  pub section: &'a str,
+  pub name: &'a str,
+  pub from: &'a str,
+  pub to: &'a str,
+}
+
+#[derive(Debug, Error)]
+pub enum ManifestError {
+  #[error("Failed to access {0}: {1}")]
+  Io(PathBuf, std::io::Error),
+  #[error("Failed to parse {0}: {1}")]
+  Json(PathBuf, serde_json::Error),
+  #[error("Failed to edit {0}: {1}")]
+  JsonEdit(PathBuf, JsonEditError),
+  #[error("{0}: {1}")]
+  PyProject(PathBuf, PyProjectError),
+  #[error("{0} is a tag-only package and has no manifest")]
+  NoManifest(PathBuf),
+}
+
+impl Package {
+  pub fn read(dir: PathBuf, kind: PackageKind) -> Result<Self, ManifestError> {
+    let Some(manifest_file) = kind.manifest_file() else {
+      return Err(ManifestError::NoManifest(dir));
+    };
+    let path = dir.join(manifest_file);
+    let source =
+      std::fs::read_to_string(&path).map_err(|error| ManifestError::Io(path.clone(), error))?;
+    let package_json = match kind {
+      PackageKind::Npm => serde_json::from_str::<PkgJson>(&source)
+        .map_err(|error| ManifestError::Json(path.clone(), error))?,
+      PackageKind::Python => PyProject::parse(&source)
+        .and_then(|pyproject| pyproject.to_pkg_json())
+        .map_err(|error| ManifestError::PyProject(path.clone(), error))?,
+      PackageKind::Tag => unreachable!(),
+    };
+
+    Ok(Self {
+      package_json,
+      dir,
+      kind,
+    })
+  }
+
+  pub fn manifest_path(&self) -> Option<PathBuf> {
+    self.kind.manifest_file().map(|file| self.dir.join(file))
+  }
+
+  /// Sets the version and dependency ranges, leaving the same name in other sections alone.
+  pub fn update_manifest(
+    &self,
+    version: Option<&str>,
+    ranges: &[RangeUpdate],
+  ) -> Result<(), ManifestError> {
+    let Some(path) = self.manifest_path() else {
+      return Ok(());
+    };
+    let source =
+      std::fs::read_to_string(&path).map_err(|error| ManifestError::Io(path.clone(), error))?;
+
+    let updated = match self.kind {
+      PackageKind::Npm => {
+        let edit_error = |error| ManifestError::JsonEdit(path.clone(), error);
+        let mut document = JsonDocument::parse(&source).map_err(edit_error)?;
+        if let Some(version) = version {
+          document
+            .set(&["version"], &serde_json::Value::from(version))
+            .map_err(edit_error)?;
+        }
+        for update in ranges {
+          let path = [update.section, update.name];
+          if document
+            .get(&path)
+            .as_ref()
+            .and_then(|range| range.as_str())
+            == Some(update.from)
+          {
+            document
+              .set(&path, &serde_json::Value::from(update.to))
+              .map_err(edit_error)?;
+          }
+        }
+        document.to_string()
+      }
+      PackageKind::Python => {
+        let mut pyproject = PyProject::parse(&source)
+          .map_err(|error| ManifestError::PyProject(path.clone(), error))?;
+        if let Some(version) = version {
+          pyproject
+            .set_version(version)
+            .map_err(|error| ManifestError::PyProject(path.clone(), error))?;
+        }
+        for update in ranges {
+          pyproject.set_dependency_range(update.section, update.name, update.from, update.to);
+        }
+        pyproject.to_string()
+      }
+      PackageKind::Tag => unreachable!(),
+    };
+
+    if updated != source {
+      std::fs::write(&path, updated).map_err(|error| ManifestError::Io(path, error))?;
+    }
+    Ok(())
+  }
+
+  pub fn update_dependency_ranges(&self, ranges: &[RangeUpdate]) -> Result<(), ManifestError> {
+    self.update_manifest(None, ranges)
+  }
+}
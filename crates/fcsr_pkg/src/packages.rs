@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 
 use serde::{Deserialize, Serialize};
 
@@ -14,17 +14,60 @@ pub enum Tool {
   Root,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Eq, PartialEq, Hash, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum PackageKind {
+  #[default]
+  Npm,
+  Python,
+  Tag,
+}
+
+impl PackageKind {
+  pub fn manifest_file(&self) -> Option<&'static str> {
+    match self {
+      Self::Npm => Some("package.json"),
+      Self::Python => Some("pyproject.toml"),
+      Self::Tag => None,
+    }
+  }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Package {
   pub package_json: PkgJson,
   pub dir: PathBuf,
+  #[serde(default)]
+  pub kind: PackageKind,
 }
 
+pub type Catalogs = HashMap<String, HashMap<String, String>>;
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Packages {
   pub tool: Tool,
   pub packages: Vec<Package>,
   pub root: Package,
+  #[serde(default)]
+  pub catalogs: Catalogs,
+}
+
+impl Packages {
+  pub fn resolve_range<'a>(&'a self, name: &str, range: &'a str) -> &'a str {
+    let Some(catalog) = range.strip_prefix("catalog:") else {
+      return range;
+    };
+    let catalog = if catalog.is_empty() {
+      "default"
+    } else {
+      catalog
+    };
+    self
+      .catalogs
+      .get(catalog)
+      .and_then(|catalog| catalog.get(name))
+      .map_or(range, String::as_str)
+  }
 }
@@ -0,0 +1,490 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::{cmp::Ordering, fmt::Display};
+
+static VERSION_REG: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(
+    r"(?ix)^\s*v?
+    (?:(?P<epoch>[0-9]+)!)?
+    (?P<release>[0-9]+(?:\.[0-9]+)*)
+    (?P<pre>[-_.]?(?P<pre_l>alpha|a|beta|b|preview|pre|c|rc)[-_.]?(?P<pre_n>[0-9]+)?)?
+    (?P<post>(?:-(?P<post_n1>[0-9]+))|(?:[-_.]?(?P<post_l>post|rev|r)[-_.]?(?P<post_n2>[0-9]+)?))?
+    (?P<dev>[-_.]?dev[-_.]?(?P<dev_n>[0-9]+)?)?
+    (?:\+(?P<local>[a-z0-9]+(?:[-_.][a-z0-9]+)*))?
+    \s*$",
+  )
+  .unwrap()
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PreKind {
+  Alpha,
+  Beta,
+  Rc,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum LocalSegment {
+  Str(String),
+  Num(u64),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+  pub epoch: u64,
+  pub release: Vec<u64>,
+  pub pre: Option<(PreKind, u64)>,
+  pub post: Option<u64>,
+  pub dev: Option<u64>,
+  pub local: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Pep440Error(String);
+
+impl Display for Pep440Error {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "\"{}\" is not a valid PEP 440 version or specifier",
+      self.0
+    )
+  }
+}
+
+impl std::error::Error for Pep440Error {}
+
+impl Version {
+  pub fn parse(input: &str) -> Result<Self, Pep440Error> {
+    let error = || Pep440Error(input.to_string());
+    let captures = VERSION_REG.captures(input).ok_or_else(error)?;
+    let parse = |segment: &str| segment.parse::<u64>().map_err(|_| error());
+    let number = |name: &str| captures.name(name).map(|m| parse(m.as_str())).transpose();
+
+    let pre = match captures.name("pre_l") {
+      Some(label) => {
+        let kind = match label.as_str().to_lowercase().as_str() {
+          "alpha" | "a" => PreKind::Alpha,
+          "beta" | "b" => PreKind::Beta,
+          _ => PreKind::Rc,
+        };
+        Some((kind, number("pre_n")?.unwrap_or(0)))
+      }
+      None => None,
+    };
+    let post = match captures.name("post") {
+      Some(_) => Some(number("post_n1")?.or(number("post_n2")?).unwrap_or(0)),
+      None => None,
+    };
+    let dev = match captures.name("dev") {
+      Some(_) => Some(number("dev_n")?.unwrap_or(0)),
+      None => None,
+    };
+
+    Ok(Self {
+      epoch: number("epoch")?.unwrap_or(0),
+      release: captures["release"]
+        .split('.')
+        .map(parse)
+        .collect::<Result<_, _>>()?,
+      pre,
+      post,
+      dev,
+      local: captures
+        .name("local")
+        .map(|m| m.as_str().to_lowercase().replace(['-', '_'], ".")),
+    })
+  }
+
+  pub fn is_prerelease(&self) -> bool {
+    self.pre.is_some() || self.dev.is_some()
+  }
+
+  pub fn public(&self) -> Self {
+    Self {
+      local: None,
+      ..self.clone()
+    }
+  }
+
+  pub fn base(&self) -> Self {
+    Self {
+      epoch: self.epoch,
+      release: self.release.clone(),
+      pre: None,
+      post: None,
+      dev: None,
+      local: None,
+    }
+  }
+
+  fn release_cmp(&self, other: &Self) -> Ordering {
+    let len = self.release.len().max(other.release.len());
+    (0..len)
+      .map(|i| {
+        let left = self.release.get(i).copied().unwrap_or(0);
+        let right = other.release.get(i).copied().unwrap_or(0);
+        left.cmp(&right)
+      })
+      .find(|ordering| ordering.is_ne())
+      .unwrap_or(Ordering::Equal)
+  }
+
+  fn local_segments(&self) -> Option<Vec<LocalSegment>> {
+    self.local.as_ref().map(|local| {
+      local
+        .split('.')
+        .map(|segment| match segment.parse() {
+          Ok(number) => LocalSegment::Num(number),
+          Err(_) => LocalSegment::Str(segment.to_string()),
+        })
+        .collect()
+    })
+  }
+}
+
+impl Ord for Version {
+  fn cmp(&self, other: &Self) -> Ordering {
+    // A dev release without a pre segment sorts before every pre-release of the same version,
+    // a final release sorts after all of them.
+    let pre_key = |version: &Self| match (version.pre, version.post, version.dev) {
+      (None, None, Some(_)) => (0, None),
+      (None, _, _) => (2, None),
+      (Some(pre), _, _) => (1, Some(pre)),
+    };
+    let dev_key = |version: &Self| match version.dev {
+      Some(dev) => (0, dev),
+      None => (1, 0),
+    };
+
+    self
+      .epoch
+      .cmp(&other.epoch)
+      .then_with(|| self.release_cmp(other))
+      .then_with(|| pre_key(self).cmp(&pre_key(other)))
+      .then_with(|| self.post.cmp(&other.post))
+      .then_with(|| dev_key(self).cmp(&dev_key(other)))
+      .then_with(|| self.local_segments().cmp(&other.local_segments()))
+  }
+}
+
+impl PartialOrd for Version {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Display for Version {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    if self.epoch != 0 {
+      write!(f, "{}!", self.epoch)?;
+    }
+    let release: Vec<String> = self.release.iter().map(u64::to_string).collect();
+    write!(f, "{}", release.join("."))?;
+    if let Some((kind, number)) = self.pre {
+      let label = match kind {
+        PreKind::Alpha => "a",
+        PreKind::Beta => "b",
+        PreKind::Rc => "rc",
+      };
+      write!(f, "{label}{number}")?;
+    }
+    if let Some(post) = self.post {
+      write!(f, ".post{post}")?;
+    }
+    if let Some(dev) = self.dev {
+      write!(f, ".dev{dev}")?;
+    }
+    if let Some(local) = &self.local {
+      write!(f, "+{local}")?;
+    }
+    Ok(())
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+  Compatible,
+  Equal,
+  NotEqual,
+  LessEqual,
+  GreaterEqual,
+  Less,
+  Greater,
+  Arbitrary,
+}
+
+impl Operator {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Compatible => "~=",
+      Self::Equal => "==",
+      Self::NotEqual => "!=",
+      Self::LessEqual => "<=",
+      Self::GreaterEqual => ">=",
+      Self::Less => "<",
+      Self::Greater => ">",
+      Self::Arbitrary => "===",
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Specifier {
+  pub operator: Operator,
+  pub version: String,
+}
+
+impl Specifier {
+  pub fn parse(input: &str) -> Result<Self, Pep440Error> {
+    let input = input.trim();
+    let error = || Pep440Error(input.to_string());
+    let operator = [
+      Operator::Arbitrary,
+      Operator::Compatible,
+      Operator::Equal,
+      Operator::NotEqual,
+      Operator::LessEqual,
+      Operator::GreaterEqual,
+      Operator::Less,
+      Operator::Greater,
+    ]
+    .into_iter()
+    .find(|operator| input.starts_with(operator.as_str()))
+    .ok_or_else(error)?;
+    let version = input[operator.as_str().len()..].trim().to_string();
+
+    match operator {
+      Operator::Arbitrary if !version.is_empty() => {}
+      Operator::Equal | Operator::NotEqual if version.ends_with(".*") => {
+        Version::parse(version.trim_end_matches(".*")).map_err(|_| error())?;
+      }
+      Operator::Compatible => {
+        if Version::parse(&version).map_err(|_| error())?.release.len() < 2 {
+          return Err(error());
+        }
+      }
+      _ => {
+        Version::parse(&version).map_err(|_| error())?;
+      }
+    }
+
+    Ok(Self { operator, version })
+  }
+
+  fn is_prerelease(&self) -> bool {
+    Version::parse(self.version.trim_end_matches(".*"))
+      .map(|version| version.is_prerelease())
+      .unwrap_or(false)
+  }
+
+  /// A specifier built by hand with an invalid version contains nothing.
+  pub fn contains(&self, candidate: &Version) -> bool {
+    if self.operator == Operator::Arbitrary {
+      return candidate.to_string().eq_ignore_ascii_case(&self.version);
+    }
+
+    if let Some(prefix) = self.version.strip_suffix(".*") {
+      let Ok(prefix) = Version::parse(prefix) else {
+        return false;
+      };
+      let matched = prefix.epoch == candidate.epoch
+        && prefix
+          .release
+          .iter()
+          .enumerate()
+          .all(|(i, segment)| candidate.release.get(i).copied().unwrap_or(0) == *segment);
+      return matched == (self.operator == Operator::Equal);
+    }
+
+    let Ok(spec) = Version::parse(&self.version) else {
+      return false;
+    };
+    match self.operator {
+      Operator::Compatible => {
+        let prefix = &spec.release[..spec.release.len() - 1];
+        candidate >= &spec
+          && candidate.epoch == spec.epoch
+          && prefix
+            .iter()
+            .enumerate()
+            .all(|(i, segment)| candidate.release.get(i).copied().unwrap_or(0) == *segment)
+      }
+      Operator::Equal | Operator::NotEqual => {
+        let candidate = if spec.local.is_none() {
+          candidate.public()
+        } else {
+          candidate.clone()
+        };
+        (candidate == spec || candidate.cmp(&spec).is_eq()) == (self.operator == Operator::Equal)
+      }
+      Operator::LessEqual => candidate.public() <= spec,
+      Operator::GreaterEqual => candidate.public() >= spec,
+      Operator::Less => {
+        candidate < &spec
+          && (spec.is_prerelease()
+            || !candidate.is_prerelease()
+            || candidate.base().release_cmp(&spec.base()).is_ne())
+      }
+      Operator::Greater => {
+        candidate > &spec
+          && (spec.post.is_some()
+            || candidate.post.is_none()
+            || candidate.base().release_cmp(&spec.base()).is_ne())
+          && candidate.local.is_none()
+      }
+      Operator::Arbitrary => unreachable!(),
+    }
+  }
+}
+
+impl Display for Specifier {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}{}", self.operator.as_str(), self.version)
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Specifiers(pub Vec<Specifier>);
+
+impl Specifiers {
+  pub fn parse(input: &str) -> Result<Self, Pep440Error> {
+    let input = input.trim();
+    if input.is_empty() {
+      return Ok(Self::default());
+    }
+    input
+      .split(',')
+      .map(Specifier::parse)
+      .collect::<Result<Vec<_>, _>>()
+      .map(Self)
+  }
+
+  pub fn contains(&self, candidate: &Version) -> bool {
+    if candidate.is_prerelease() && !self.0.iter().any(Specifier::is_prerelease) {
+      return false;
+    }
+    self.0.iter().all(|specifier| specifier.contains(candidate))
+  }
+}
+
+impl Display for Specifiers {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let specifiers: Vec<String> = self.0.iter().map(Specifier::to_string).collect();
+    f.write_str(&specifiers.join(","))
+  }
+}
+
+pub fn canonicalize_name(name: &str) -> String {
+  let mut canonical = String::with_capacity(name.len());
+  let mut last_was_separator = false;
+  for ch in name.trim().chars() {
+    if matches!(ch, '-' | '_' | '.') {
+      if !last_was_separator {
+        canonical.push('-');
+      }
+      last_was_separator = true;
+    } else {
+      canonical.push(ch.to_ascii_lowercase());
+      last_was_separator = false;
+    }
+  }
+  canonical
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn v(input: &str) -> Version {
+    Version::parse(input).unwrap()
+  }
+
+  #[test]
+  fn parse_and_normalize() {
+    assert_eq!(v("1.0").to_string(), "1.0");
+    assert_eq!(v("v1.0.0-alpha.1").to_string(), "1.0.0a1");
+    assert_eq!(v("1.0c2").to_string(), "1.0rc2");
+    assert_eq!(v("1.0-1").to_string(), "1.0.post1");
+    assert_eq!(v("2!1.0.dev3+Ubuntu-1").to_string(), "2!1.0.dev3+ubuntu.1");
+    assert!(Version::parse("1.0.0-beta+").is_err());
+    assert!(Version::parse("latest").is_err());
+    assert!(Version::parse("1.99999999999999999999").is_err());
+    assert!(Version::parse("1.0.post99999999999999999999").is_err());
+  }
+
+  #[test]
+  fn ordering() {
+    let ordered = [
+      "1.0.dev456",
+      "1.0a1",
+      "1.0a2.dev456",
+      "1.0a12",
+      "1.0b1.dev456",
+      "1.0b2.post345.dev456",
+      "1.0b2.post345",
+      "1.0rc1",
+      "1.0",
+      "1.0+abc.5",
+      "1.0+5",
+      "1.0.post456.dev34",
+      "1.0.post456",
+      "1.1.dev1",
+      "1!0.1",
+    ];
+    for pair in ordered.windows(2) {
+      assert!(v(pair[0]) < v(pair[1]), "{} < {}", pair[0], pair[1]);
+    }
+    assert_eq!(v("1.0").cmp(&v("1.0.0")), Ordering::Equal);
+  }
+
+  #[test]
+  fn specifiers() {
+    let cases = [
+      (">=1.0,<2", "1.5.0", true),
+      (">=1.0,<2", "2.0", false),
+      ("~=1.4.5", "1.4.9", true),
+      ("~=1.4.5", "1.5.0", false),
+      ("~=1.4", "1.9", true),
+      ("==1.4.*", "1.4.2", true),
+      ("==1.4.*", "1.5", false),
+      ("!=1.4.*", "1.5", true),
+      ("==1.0", "1.0.0+local", true),
+      ("==1.0+local", "1.0", false),
+      ("<2.0", "2.0rc1", false),
+      (">1.0", "1.0.post1", false),
+      (">1.0", "1.0.1", true),
+      (">=1.0", "2.0b1", false),
+      (">=1.0b1", "2.0b1", true),
+      ("===1.0", "1.0", true),
+      ("", "3.0", true),
+    ];
+    for (spec, version, expected) in cases {
+      assert_eq!(
+        Specifiers::parse(spec).unwrap().contains(&v(version)),
+        expected,
+        "{version} in {spec}"
+      );
+    }
+    assert!(Specifiers::parse("~=1").is_err());
+    assert!(Specifiers::parse("^1.0.0").is_err());
+    assert!(Specifiers::parse(">=1.99999999999999999999").is_err());
+
+    let invalid = Specifier {
+      operator: Operator::GreaterEqual,
+      version: String::from("latest"),
+    };
+    assert!(!invalid.contains(&v("1.0")));
+    let invalid = Specifier {
+      operator: Operator::Equal,
+      version: String::from("latest.*"),
+    };
+    assert!(!invalid.contains(&v("1.0")));
+  }
+
+  #[test]
+  fn canonical_names() {
+    assert_eq!(canonicalize_name("My_Client.Lib"), "my-client-lib");
+    assert_eq!(canonicalize_name("a--b__c"), "a-b-c");
+  }
+}
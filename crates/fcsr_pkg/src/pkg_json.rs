@@ -1,5 +1,5 @@
-use serde::{de::Visitor, Deserialize, Serialize};
-use std::{collections::HashMap, fmt::Display};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct PublishConfig {
@@ -31,7 +31,39 @@ pub struct PkgJson {
   #[serde(skip_serializing_if = "Option::is_none", default)]
   pub publish_config: Option<PublishConfig>,
   #[serde(skip_serializing_if = "Option::is_none", default)]
-  pub workspaces: Option<Vec<String>>,
+  pub workspaces: Option<Workspaces>,
+  /// Every requirement of a pyproject.toml package in declaration order. The dependency maps keep a
+  /// single range per name, while a name can be required several times, e.g. behind markers.
+  #[serde(skip)]
+  pub requirements: Vec<PyRequirement>,
+}
+
+/// A `[project]` requirement, `optional` when it belongs to an extra.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct PyRequirement {
+  pub optional: bool,
+  pub name: String,
+  pub range: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(untagged)]
+pub enum Workspaces {
+  Packages(Vec<String>),
+  Object {
+    #[serde(default)]
+    packages: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    nohoist: Option<Vec<String>>,
+  },
+}
+
+impl Workspaces {
+  pub fn packages(&self) -> &[String] {
+    match self {
+      Self::Packages(packages) | Self::Object { packages, .. } => packages,
+    }
+  }
 }
 
 impl PkgJson {
@@ -47,6 +79,7 @@ impl PkgJson {
       private: None,
       publish_config: None,
       workspaces: None,
+      requirements: vec![],
     }
   }
 }
@@ -54,7 +87,7 @@ impl PkgJson {
 pub mod access_type {
   use super::*;
 
-  #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+  #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, schemars::JsonSchema)]
   #[serde(rename_all = "camelCase")]
   pub enum AccessType {
     Public,
@@ -159,6 +192,7 @@ mod test {
         registry: Some(String::from("465")),
       }),
       workspaces: None,
+      requirements: vec![],
     };
     assert_eq!(
       r#"{"name":"123456","version":"321","dependencies":{},"peerDependencies":{},"devDependencies":{},"optionalDependencies":{},"private":true,"publishConfig":{"access":"public","directory":"6666","registry":"465"}}"#,
@@ -188,6 +222,7 @@ mod test {
         resolutions: None,
         private: None,
         workspaces: None,
+        requirements: vec![],
         publish_config: Some(PublishConfig {
           access: Some(access_type::AccessType::Public),
           directory: None,
@@ -196,4 +231,29 @@ mod test {
       }
     );
   }
+
+  #[test]
+  fn deserialize_workspaces() {
+    let parse = |json: serde_json::Value| {
+      serde_json::from_value::<PkgJson>(json)
+        .unwrap()
+        .workspaces
+        .unwrap()
+    };
+    assert_eq!(
+      parse(json!({"name": "a", "version": "1.0.0", "workspaces": ["packages/*"]})).packages(),
+      ["packages/*"]
+    );
+    assert_eq!(
+      parse(json!({
+        "name": "a",
+        "version": "1.0.0",
+        "workspaces": {"packages": ["packages/*"], "nohoist": ["**/react-native"]}
+      })),
+      Workspaces::Object {
+        packages: vec![String::from("packages/*")],
+        nohoist: Some(vec![String::from("**/react-native")]),
+      }
+    );
+  }
 }
@@ -0,0 +1,375 @@
+use crate::{pep440::canonicalize_name, PkgJson, PyRequirement};
+use std::{collections::HashMap, fmt::Display, ops::Range};
+use thiserror::Error;
+use toml_edit::{DocumentMut, Item, Value};
+
+const PRIVATE_CLASSIFIER: &str = "Private :: Do Not Upload";
+
+#[derive(Debug, Error)]
+pub enum PyProjectError {
+  #[error("Failed to parse pyproject.toml: {0}")]
+  Toml(#[from] toml_edit::TomlError),
+  #[error(
+    "pyproject.toml has no `[project] {0}` string, fcsr only supports static PEP 621 metadata"
+  )]
+  MissingField(&'static str),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Requirement {
+  pub name: String,
+  pub specifier: String,
+  pub url: Option<String>,
+  specifier_span: Range<usize>,
+}
+
+impl Requirement {
+  pub fn parse(input: &str) -> Option<Self> {
+    let name_end = input
+      .char_indices()
+      .find(|(_, ch)| !(ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.')))
+      .map(|(index, _)| index)
+      .unwrap_or(input.len());
+    let name = input[..name_end].trim();
+    if name.is_empty() || name != &input[..name_end] {
+      return None;
+    }
+
+    let mut cursor = skip_whitespace(input, name_end);
+    if input[cursor..].starts_with('[') {
+      cursor = skip_whitespace(input, cursor + input[cursor..].find(']')? + 1);
+    }
+
+    let marker_start = input.find(';').unwrap_or(input.len());
+    if marker_start < cursor {
+      return None;
+    }
+
+    let rest = &input[cursor..marker_start];
+    if let Some(url) = rest.strip_prefix('@') {
+      return Some(Self {
+        name: canonicalize_name(name),
+        specifier: String::new(),
+        url: Some(url.trim().to_string()),
+        specifier_span: cursor..cursor,
+      });
+    }
+
+    let (start, end) = match rest.trim_end().strip_prefix('(') {
+      Some(inner) => {
+        let inner = inner.strip_suffix(')')?;
+        (cursor + 1, cursor + 1 + inner.len())
+      }
+      None => (cursor, cursor + rest.trim_end().len()),
+    };
+
+    Some(Self {
+      name: canonicalize_name(name),
+      specifier: input[start..end].trim().to_string(),
+      url: None,
+      specifier_span: start..end,
+    })
+  }
+
+  fn replace_specifier(&self, input: &str, specifier: &str) -> String {
+    let Range { start, end } = self.specifier_span;
+    format!("{}{specifier}{}", &input[..start], &input[end..])
+  }
+}
+
+fn skip_whitespace(input: &str, from: usize) -> usize {
+  from + input[from..].len() - input[from..].trim_start().len()
+}
+
+#[derive(Debug, Clone)]
+pub struct PyProject {
+  document: DocumentMut,
+}
+
+impl PyProject {
+  pub fn parse(source: &str) -> Result<Self, PyProjectError> {
+    Ok(Self {
+      document: source.parse()?,
+    })
+  }
+
+  fn project_str(&self, key: &'static str) -> Result<&str, PyProjectError> {
+    self
+      .document
+      .get("project")
+      .and_then(|project| project.get(key))
+      .and_then(Item::as_str)
+      .ok_or(PyProjectError::MissingField(key))
+  }
+
+  pub fn name(&self) -> Result<&str, PyProjectError> {
+    self.project_str("name")
+  }
+
+  pub fn version(&self) -> Result<&str, PyProjectError> {
+    self.project_str("version")
+  }
+
+  fn requirement_arrays(&self) -> Vec<(bool, &toml_edit::Array)> {
+    let Some(project) = self.document.get("project") else {
+      return vec![];
+    };
+    let mut arrays = vec![];
+    if let Some(dependencies) = project.get("dependencies").and_then(Item::as_array) {
+      arrays.push((false, dependencies));
+    }
+    if let Some(extras) = project
+      .get("optional-dependencies")
+      .and_then(Item::as_table_like)
+    {
+      for (_, extra) in extras.iter() {
+        if let Some(extra) = extra.as_array() {
+          arrays.push((true, extra));
+        }
+      }
+    }
+    arrays
+  }
+
+  pub fn to_pkg_json(&self) -> Result<PkgJson, PyProjectError> {
+    let mut package_json =
+      PkgJson::new(canonicalize_name(self.name()?), self.version()?.to_string());
+    let mut dependencies = HashMap::new();
+    let mut optional_dependencies = HashMap::new();
+
+    for (optional, array) in self.requirement_arrays() {
+      let section = if optional {
+        &mut optional_dependencies
+      } else {
+        &mut dependencies
+      };
+      for requirement in array
+        .iter()
+        .filter_map(Value::as_str)
+        .filter_map(Requirement::parse)
+      {
+        let range = match requirement.url {
+          Some(url) => format!("@ {url}"),
+          None => requirement.specifier,
+        };
+        section.insert(requirement.name.clone(), range.clone());
+        package_json.requirements.push(PyRequirement {
+          optional,
+          name: requirement.name,
+          range,
+        });
+      }
+    }
+
+    let private = self
+      .document
+      .get("project")
+      .and_then(|project| project.get("classifiers"))
+      .and_then(Item::as_array)
+      .map(|classifiers| {
+        classifiers
+          .iter()
+          .any(|classifier| classifier.as_str() == Some(PRIVATE_CLASSIFIER))
+      })
+      .unwrap_or(false);
+
+    package_json.dependencies = (!dependencies.is_empty()).then_some(dependencies);
+    package_json.optional_dependencies =
+      (!optional_dependencies.is_empty()).then_some(optional_dependencies);
+    package_json.private = private.then_some(true);
+    Ok(package_json)
+  }
+
+  pub fn set_version(&mut self, version: &str) -> Result<(), PyProjectError> {
+    let item = self
+      .document
+      .get_mut("project")
+      .and_then(|project| project.get_mut("version"))
+      .ok_or(PyProjectError::MissingField("version"))?;
+    replace_str(item.as_value_mut(), version);
+    Ok(())
+  }
+
+  /// Rewrites the requirements on `name` set to `from` in `section`, `dependencies` for the
+  /// `[project]` array or `optionalDependencies` for the extras.
+  pub fn set_dependency_range(&mut self, section: &str, name: &str, from: &str, to: &str) -> bool {
+    let name = canonicalize_name(name);
+    let Some(project) = self.document.get_mut("project") else {
+      return false;
+    };
+
+    let mut arrays = vec![];
+    if let Some(table) = project.as_table_like_mut() {
+      for (key, item) in table.iter_mut() {
+        match (key.get(), section) {
+          ("dependencies", "dependencies") => arrays.extend(item.as_array_mut()),
+          ("optional-dependencies", "optionalDependencies") => {
+            if let Some(extras) = item.as_table_like_mut() {
+              arrays.extend(
+                extras
+                  .iter_mut()
+                  .filter_map(|(_, extra)| extra.as_array_mut()),
+              );
+            }
+          }
+          _ => {}
+        }
+      }
+    }
+
+    let mut changed = false;
+    for array in arrays {
+      for value in array.iter_mut() {
+        let Some(raw) = value.as_str() else { continue };
+        let Some(requirement) = Requirement::parse(raw) else {
+          continue;
+        };
+        if requirement.name != name
+          || requirement.url.is_some()
+          || requirement.specifier != from
+          || requirement.specifier == to
+        {
+          continue;
+        }
+        let updated = requirement.replace_specifier(raw, to);
+        replace_str(Some(value), &updated);
+        changed = true;
+      }
+    }
+    changed
+  }
+}
+
+fn replace_str(value: Option<&mut Value>, content: &str) {
+  if let Some(value) = value {
+    let decor = value.decor().clone();
+    *value = Value::from(content);
+    *value.decor_mut() = decor;
+  }
+}
+
+impl Display for PyProject {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.document)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  const SOURCE: &str = r#"[build-system]
+requires = ["hatchling"]
+
+[project]
+name = "Acme_Client"
+version = "1.2.0"  # bumped by fcsr
+classifiers = [
+  "Private :: Do Not Upload",
+]
+dependencies = [
+  "acme-core>=1.0,<2 ; python_version >= '3.9'",
+  'requests (>=2.31)',
+  "acme.utils[fast]",
+  "acme-local @ file:///tmp/acme-local",
+]
+
+[project.optional-dependencies]
+cli = ["acme-cli~=0.4"]
+"#;
+
+  #[test]
+  fn parse_requirements() {
+    let requirement = Requirement::parse("Foo.Bar [x, y] (>=1, <2) ; os_name == 'nt'").unwrap();
+    assert_eq!(requirement.name, "foo-bar");
+    assert_eq!(requirement.specifier, ">=1, <2");
+    assert_eq!(Requirement::parse("foo").unwrap().specifier, "");
+    assert_eq!(
+      Requirement::parse("foo @ https://x/y.whl")
+        .unwrap()
+        .url
+        .as_deref(),
+      Some("https://x/y.whl")
+    );
+    assert!(Requirement::parse(" ; foo").is_none());
+  }
+
+  #[test]
+  fn to_pkg_json() {
+    let package_json = PyProject::parse(SOURCE).unwrap().to_pkg_json().unwrap();
+    assert_eq!(package_json.name, "acme-client");
+    assert_eq!(package_json.version, "1.2.0");
+    assert_eq!(package_json.private, Some(true));
+    let dependencies = package_json.dependencies.unwrap();
+    assert_eq!(dependencies["acme-core"], ">=1.0,<2");
+    assert_eq!(dependencies["requests"], ">=2.31");
+    assert_eq!(dependencies["acme-utils"], "");
+    assert_eq!(dependencies["acme-local"], "@ file:///tmp/acme-local");
+    assert_eq!(
+      package_json.optional_dependencies.unwrap()["acme-cli"],
+      "~=0.4"
+    );
+  }
+
+  #[test]
+  fn dynamic_version_is_rejected() {
+    let source = "[project]\nname = \"a\"\ndynamic = [\"version\"]\n";
+    assert!(matches!(
+      PyProject::parse(source).unwrap().to_pkg_json(),
+      Err(PyProjectError::MissingField("version"))
+    ));
+  }
+
+  #[test]
+  fn edits_keep_formatting() {
+    let mut pyproject = PyProject::parse(SOURCE).unwrap();
+    pyproject.set_version("1.3.0").unwrap();
+    assert!(pyproject.set_dependency_range("dependencies", "acme_core", ">=1.0,<2", ">=2.0.0"));
+    assert!(pyproject.set_dependency_range("optionalDependencies", "acme-cli", "~=0.4", "~=0.5"));
+    assert!(!pyproject.set_dependency_range("optionalDependencies", "acme-cli", "~=0.4", "~=0.5"));
+    assert!(!pyproject.set_dependency_range("dependencies", "acme-cli", "~=0.5", "~=0.6"));
+
+    let expected = SOURCE
+      .replace("\"1.2.0\"  #", "\"1.3.0\"  #")
+      .replace("acme-core>=1.0,<2 ;", "acme-core>=2.0.0 ;")
+      .replace("acme-cli~=0.4", "acme-cli~=0.5");
+    assert_eq!(pyproject.to_string(), expected);
+  }
+
+  #[test]
+  fn keep_every_requirement() {
+    let source = r#"[project]
+name = "app"
+version = "1.0.0"
+dependencies = [
+  "foo>=1 ; python_version < '3.8'",
+  "foo>=2 ; python_version >= '3.8'",
+]
+
+[project.optional-dependencies]
+dev = ["foo>=1"]
+"#;
+    let mut pyproject = PyProject::parse(source).unwrap();
+    let requirements: Vec<_> = pyproject
+      .to_pkg_json()
+      .unwrap()
+      .requirements
+      .into_iter()
+      .map(|requirement| (requirement.optional, requirement.range))
+      .collect();
+    assert_eq!(
+      requirements,
+      vec![
+        (false, String::from(">=1")),
+        (false, String::from(">=2")),
+        (true, String::from(">=1")),
+      ]
+    );
+
+    assert!(pyproject.set_dependency_range("dependencies", "foo", ">=1", ">=1.5"));
+    assert_eq!(
+      pyproject.to_string(),
+      source.replace("\"foo>=1 ;", "\"foo>=1.5 ;")
+    );
+  }
+}
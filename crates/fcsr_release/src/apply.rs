@@ -0,0 +1,588 @@
+use crate::{
+  assemble::depends_on, releasable_packages, update_range, PackageRelease, ReleaseOptions,
+  ReleasePlan,
+};
+use fcsr_changeset::{
+  read_pending_versions, remove_changeset, write_pending_versions, ReadError, VersionType,
+};
+use fcsr_config::{
+  config::DEFAULT_CHANGELOG_FILE,
+  dependency_graph::{get_all_dependencies, satisfies, DependencyKind, DependencySpec},
+  written::UpdateInternalDependencies,
+};
+use fcsr_pkg::{
+  packages::{Package, PackageKind, Packages},
+  ManifestError, RangeUpdate,
+};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ApplyError {
+  #[error(transparent)]
+  Manifest(#[from] ManifestError),
+  #[error(transparent)]
+  Changeset(#[from] ReadError),
+  #[error("Failed to write {0}: {1}")]
+  Changelog(PathBuf, std::io::Error),
+}
+
+/// The `(section, name, range, new range)` of every internal dependency range that needs
+/// rewriting.
+fn updated_dependencies(
+  package: &Package,
+  plan: &ReleasePlan,
+  options: &ReleaseOptions,
+) -> Vec<(DependencyKind, String, String, String)> {
+  let is_released = plan.get(&package.package_json.name).is_some();
+  let mut updated = vec![];
+
+  for (kind, name, range) in get_all_dependencies(&package.package_json) {
+    let Some(release) = plan.get(DependencySpec::parse(&name, &range).name) else {
+      continue;
+    };
+    let out_of_range = satisfies(package.kind, &range, &release.new_version) == Some(false);
+    let wants_update = is_released
+      && (options.update_internal_dependencies == UpdateInternalDependencies::Patch
+        || release.r#type >= VersionType::Minor);
+    if !(out_of_range || wants_update) {
+      continue;
+    }
+    if let Some(new_range) = update_range(package.kind, &range, &release.new_version) {
+      if new_range != range {
+        updated.push((kind, name, range, new_range));
+      }
+    }
+  }
+  updated
+}
+
+fn changelog_entry(package: &Package, release: &PackageRelease, plan: &ReleasePlan) -> String {
+  let mut sections: Vec<(VersionType, Vec<String>)> = vec![
+    (VersionType::Major, vec![]),
+    (VersionType::Minor, vec![]),
+    (VersionType::Patch, vec![]),
+  ];
+
+  for changeset in plan
+    .changesets
+    .iter()
+    .filter(|changeset| release.changesets.contains(&changeset.id))
+  {
+    let r#type = changeset
+      .releases
+      .iter()
+      .find(|changeset_release| changeset_release.name == release.name)
+      .map(|changeset_release| changeset_release.r#type)
+      .unwrap_or(VersionType::Patch);
+    if let Some((_, lines)) = sections.iter_mut().find(|(section, _)| *section == r#type) {
+      lines.push(format!("- {}", changeset.summary.replace('\n', "\n  ")));
+    }
+  }
+
+  let updated_dependencies: Vec<String> = plan
+    .releases
+    .iter()
+    .filter(|dependency| depends_on(&package.package_json, &dependency.name))
+    .map(|dependency| format!("  - {}@{}", dependency.name, dependency.new_version))
+    .collect();
+  if !updated_dependencies.is_empty() {
+    sections[2].1.push(format!(
+      "- Updated dependencies\n{}",
+      updated_dependencies.join("\n")
+    ));
+  }
+
+  let mut entry = format!("## {}\n", release.new_version);
+  for (r#type, lines) in sections {
+    if lines.is_empty() {
+      continue;
+    }
+    let title = match r#type {
+      VersionType::Major => "Major",
+      VersionType::Minor => "Minor",
+      _ => "Patch",
+    };
+    entry.push_str(&format!("\n### {title} Changes\n\n{}\n", lines.join("\n")));
+  }
+  entry
+}
+
+fn write_changelog(package: &Package, path: &str, entry: &str) -> Result<PathBuf, ApplyError> {
+  let path = package.dir.join(path);
+  let title = format!("# {}", package.package_json.name);
+  let contents = match std::fs::read_to_string(&path) {
+    Ok(existing) => match existing.strip_prefix(&title) {
+      Some(rest) => format!("{title}\n\n{entry}\n{}", rest.trim_start()),
+      None => format!("{title}\n\n{entry}\n{existing}"),
+    },
+    Err(_) => format!("{title}\n\n{entry}"),
+  };
+  if let Some(parent) = path.parent() {
+    std::fs::create_dir_all(parent).map_err(|error| ApplyError::Changelog(path.clone(), error))?;
+  }
+  std::fs::write(&path, contents.trim_end().to_string() + "\n")
+    .map_err(|error| ApplyError::Changelog(path.clone(), error))?;
+  Ok(path)
+}
+
+pub fn apply_release_plan(
+  plan: &ReleasePlan,
+  packages: &Packages,
+  root: &Path,
+  options: &ReleaseOptions,
+) -> Result<Vec<PathBuf>, ApplyError> {
+  let mut touched = vec![];
+
+  let mut all_packages = releasable_packages(packages);
+  if !all_packages.contains(&&packages.root) {
+    all_packages.push(&packages.root);
+  }
+
+  let mut pending_versions = read_pending_versions(root)?;
+  let mut pending_changed = false;
+
+  for package in all_packages {
+    if options.ignore.contains(&package.package_json.name) {
+      continue;
+    }
+    let release = plan.get(&package.package_json.name);
+    let dependencies = updated_dependencies(package, plan, options);
+    if release.is_none() && dependencies.is_empty() {
+      continue;
+    }
+
+    if let (PackageKind::Tag, Some(release)) = (package.kind, release) {
+      pending_versions.insert(release.name.clone(), release.new_version.clone());
+      pending_changed = true;
+    }
+
+    let ranges: Vec<_> = dependencies
+      .iter()
+      .map(|(kind, name, from, to)| RangeUpdate {
+        section: kind.as_str(),
+        name,
+        from,
+        to,
+      })
+      .collect();
+    package.update_manifest(release.map(|release| release.new_version.as_str()), &ranges)?;
+    touched.extend(package.manifest_path());
+
+    let changelog = match options.changelog_paths.get(&package.package_json.name) {
+      Some(path) => path.as_deref(),
+      None => options.changelog.then_some(DEFAULT_CHANGELOG_FILE),
+    };
+    if let (Some(release), Some(path)) = (release, changelog) {
+      touched.push(write_changelog(
+        package,
+        path,
+        &changelog_entry(package, release, plan),
+      )?);
+    }
+  }
+
+  if pending_changed {
+    write_pending_versions(root, &pending_versions)?;
+  }
+
+  for changeset in plan.changesets.iter() {
+    remove_changeset(root, changeset)?;
+  }
+
+  Ok(touched)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::assemble_release_plan;
+  use fcsr_changeset::{Changeset, Release};
+  use fcsr_config::{config::PrivatePackages, written::PrivateChangesets};
+  use fcsr_pkg::{packages::PackageKind, PkgJson};
+  use fcsr_test_utils::{workspace, write_package};
+  use std::collections::HashMap;
+
+  fn changeset(id: &str, releases: &[(&str, VersionType)]) -> Changeset {
+    Changeset {
+      id: id.to_string(),
+      summary: format!("{id} summary"),
+      releases: releases
+        .iter()
+        .map(|(name, r#type)| Release {
+          name: name.to_string(),
+          r#type: *r#type,
+        })
+        .collect(),
+    }
+  }
+
+  #[test]
+  fn version_npm_and_python_packages() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    std::fs::create_dir_all(root.join(".changeset")).unwrap();
+    std::fs::write(root.join(".changeset/big-change.md"), "").unwrap();
+
+    let packages = workspace(
+      root,
+      &[
+        (
+          "a",
+          PackageKind::Npm,
+          "{\n  \"name\": \"a\",\n  \"version\": \"1.0.0\"\n}\n",
+        ),
+        (
+          "b",
+          PackageKind::Npm,
+          r#"{"name":"b","version":"1.0.0","scripts":{"build":"tsc"},"dependencies":{"a":"^1.0.0"}}"#,
+        ),
+        (
+          "py-core",
+          PackageKind::Python,
+          "[project]\nname = \"py_core\"\nversion = \"0.4.1\"  # keep me\n",
+        ),
+        (
+          "py-client",
+          PackageKind::Python,
+          "[project]\nname = \"py-client\"\nversion = \"1.0\"\ndependencies = [\n  \"py-core>=0.4,<0.5\",\n]\n",
+        ),
+      ],
+    );
+
+    let plan = assemble_release_plan(
+      &[changeset(
+        "big-change",
+        &[("a", VersionType::Major), ("py-core", VersionType::Minor)],
+      )],
+      &packages,
+      &ReleaseOptions::default(),
+    )
+    .unwrap();
+    let versions: Vec<_> = plan
+      .releases
+      .iter()
+      .map(|release| (release.name.as_str(), release.new_version.as_str()))
+      .collect();
+    assert_eq!(
+      versions,
+      vec![
+        ("a", "2.0.0"),
+        ("b", "1.0.1"),
+        ("py-client", "1.0.1"),
+        ("py-core", "0.5.0")
+      ]
+    );
+
+    apply_release_plan(&plan, &packages, root, &ReleaseOptions::default()).unwrap();
+
+    let read = |path: &str| std::fs::read_to_string(root.join(path)).unwrap();
+    assert_eq!(
+      read("a/package.json"),
+      "{\n  \"name\": \"a\",\n  \"version\": \"2.0.0\"\n}\n"
+    );
+    assert_eq!(
+      read("b/package.json"),
+      r#"{"name":"b","version":"1.0.1","scripts":{"build":"tsc"},"dependencies":{"a":"^2.0.0"}}"#
+    );
+    assert_eq!(
+      read("py-core/pyproject.toml"),
+      "[project]\nname = \"py_core\"\nversion = \"0.5.0\"  # keep me\n"
+    );
+    assert!(read("py-client/pyproject.toml").contains("\"py-core>=0.5.0\","));
+    assert_eq!(
+      read("b/CHANGELOG.md"),
+      "# b\n\n## 1.0.1\n\n### Patch Changes\n\n- Updated dependencies\n  - a@2.0.0\n"
+    );
+    assert_eq!(
+      read("a/CHANGELOG.md"),
+      "# a\n\n## 2.0.0\n\n### Major Changes\n\n- big-change summary\n"
+    );
+    assert!(!root.join(".changeset/big-change.md").exists());
+  }
+
+  #[test]
+  fn update_ranges_per_section() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    std::fs::create_dir_all(root.join(".changeset")).unwrap();
+    std::fs::write(root.join(".changeset/one.md"), "").unwrap();
+    let packages = workspace(
+      root,
+      &[
+        ("a", PackageKind::Npm, r#"{"name":"a","version":"1.0.0"}"#),
+        (
+          "b",
+          PackageKind::Npm,
+          r#"{"name":"b","version":"1.0.0","devDependencies":{"a":"workspace:*"},"peerDependencies":{"a":"^1.0.0"}}"#,
+        ),
+      ],
+    );
+
+    let plan = assemble_release_plan(
+      &[changeset("one", &[("a", VersionType::Major)])],
+      &packages,
+      &ReleaseOptions::default(),
+    )
+    .unwrap();
+    apply_release_plan(&plan, &packages, root, &ReleaseOptions::default()).unwrap();
+
+    let b = std::fs::read_to_string(root.join("b/package.json")).unwrap();
+    assert!(
+      b.contains(r#""devDependencies":{"a":"workspace:*"},"peerDependencies":{"a":"^2.0.0"}"#),
+      "{b}"
+    );
+  }
+
+  #[test]
+  fn update_python_ranges_per_requirement() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    std::fs::create_dir_all(root.join(".changeset")).unwrap();
+    std::fs::write(root.join(".changeset/one.md"), "").unwrap();
+    let packages = workspace(
+      root,
+      &[
+        (
+          "py-core",
+          PackageKind::Python,
+          "[project]\nname = \"py-core\"\nversion = \"0.4.1\"\n",
+        ),
+        (
+          "py-client",
+          PackageKind::Python,
+          r#"[project]
+name = "py-client"
+version = "1.0.0"
+dependencies = [
+  "py-core>=0.4,<0.5 ; python_version >= '3.9'",
+  "py-core>=0.3,<0.5 ; python_version < '3.9'",
+]
+
+[project.optional-dependencies]
+test = ["py-core~=0.4.1"]
+"#,
+        ),
+      ],
+    );
+
+    let plan = assemble_release_plan(
+      &[changeset("one", &[("py-core", VersionType::Minor)])],
+      &packages,
+      &ReleaseOptions::default(),
+    )
+    .unwrap();
+    apply_release_plan(&plan, &packages, root, &ReleaseOptions::default()).unwrap();
+
+    let client = std::fs::read_to_string(root.join("py-client/pyproject.toml")).unwrap();
+    assert!(
+      client.contains(
+        "  \"py-core>=0.5.0 ; python_version >= '3.9'\",\n  \"py-core>=0.5.0 ; python_version < '3.9'\","
+      ),
+      "{client}"
+    );
+    assert!(client.contains(r#"test = ["py-core~=0.5.0"]"#), "{client}");
+  }
+
+  #[test]
+  fn version_tag_packages_in_groups() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    std::fs::create_dir_all(root.join(".changeset")).unwrap();
+    std::fs::create_dir_all(root.join("charts/api")).unwrap();
+    for id in ["one", "two"] {
+      std::fs::write(root.join(format!(".changeset/{id}.md")), "").unwrap();
+    }
+    let mut packages = workspace(
+      root,
+      &[
+        ("a", PackageKind::Npm, r#"{"name":"a","version":"1.0.0"}"#),
+        ("b", PackageKind::Npm, r#"{"name":"b","version":"1.0.0"}"#),
+        ("c", PackageKind::Npm, r#"{"name":"c","version":"2.0.0"}"#),
+      ],
+    );
+    packages.packages.push(Package {
+      package_json: PkgJson::new(String::from("api-chart"), String::from("1.2.0")),
+      dir: root.join("charts/api"),
+      kind: PackageKind::Tag,
+    });
+    let options = ReleaseOptions {
+      fixed: vec![vec![String::from("a"), String::from("api-chart")]],
+      linked: vec![vec![String::from("b"), String::from("c")]],
+      changelog_paths: HashMap::from([
+        (String::from("b"), None),
+        (String::from("c"), Some(String::from("docs/CHANGES.md"))),
+      ]),
+      ..ReleaseOptions::default()
+    };
+
+    let plan = assemble_release_plan(
+      &[
+        changeset(
+          "one",
+          &[("a", VersionType::Minor), ("b", VersionType::Patch)],
+        ),
+        changeset("two", &[("c", VersionType::Minor)]),
+      ],
+      &packages,
+      &options,
+    )
+    .unwrap();
+    let versions: Vec<_> = plan
+      .releases
+      .iter()
+      .map(|release| (release.name.as_str(), release.new_version.as_str()))
+      .collect();
+    assert_eq!(
+      versions,
+      vec![
+        ("a", "1.3.0"),
+        ("api-chart", "1.3.0"),
+        ("b", "2.1.0"),
+        ("c", "2.1.0")
+      ]
+    );
+
+    let touched = apply_release_plan(&plan, &packages, root, &options).unwrap();
+    assert_eq!(
+      read_pending_versions(root).unwrap(),
+      [(String::from("api-chart"), String::from("1.3.0"))].into()
+    );
+    assert!(root.join("charts/api/CHANGELOG.md").exists());
+    assert!(!root.join("b/CHANGELOG.md").exists());
+    assert!(root.join("c/docs/CHANGES.md").exists());
+    assert!(!root.join("charts/api/package.json").exists());
+    assert!(!touched.iter().any(|path| path.ends_with("charts/api")));
+  }
+
+  #[test]
+  fn skip_private_packages() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let packages = workspace(
+      root,
+      &[
+        ("a", PackageKind::Npm, r#"{"name":"a","version":"1.0.0"}"#),
+        (
+          "example",
+          PackageKind::Npm,
+          r#"{"name":"example","version":"1.0.0","private":true,"dependencies":{"a":"^1.0.0"}}"#,
+        ),
+        (
+          "tools",
+          PackageKind::Npm,
+          r#"{"name":"tools","version":"1.0.0","private":true,"peerDependencies":{"a":"^1.0.0"}}"#,
+        ),
+      ],
+    );
+    let changesets = [changeset(
+      "one",
+      &[("a", VersionType::Major), ("example", VersionType::Minor)],
+    )];
+    let released = |options: &ReleaseOptions| {
+      let plan = assemble_release_plan(&changesets, &packages, options).unwrap();
+      plan
+        .releases
+        .iter()
+        .map(|release| format!("{}@{}", release.name, release.new_version))
+        .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+      released(&ReleaseOptions::default()),
+      vec!["a@2.0.0", "example@1.1.0", "tools@2.0.0"]
+    );
+    let options = ReleaseOptions {
+      fixed: vec![vec![String::from("a"), String::from("tools")]],
+      private_packages: PrivatePackages {
+        version: false,
+        tag: false,
+        changesets: PrivateChangesets::Ignore,
+      },
+      ..ReleaseOptions::default()
+    };
+    assert_eq!(released(&options), vec!["a@2.0.0"]);
+
+    let private_only = [changeset("two", &[("example", VersionType::Minor)])];
+    let plan = assemble_release_plan(&private_only, &packages, &options).unwrap();
+    assert!(plan.changesets.is_empty());
+    assert!(plan.releases.is_empty());
+  }
+
+  #[test]
+  fn leave_ignored_changesets() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    std::fs::create_dir_all(root.join(".changeset")).unwrap();
+    for id in ["one", "two"] {
+      std::fs::write(root.join(format!(".changeset/{id}.md")), "").unwrap();
+    }
+    let packages = workspace(
+      root,
+      &[
+        ("a", PackageKind::Npm, r#"{"name":"a","version":"1.0.0"}"#),
+        (
+          "b",
+          PackageKind::Npm,
+          r#"{"name":"b","version":"1.0.0","peerDependencies":{"a":"^1.0.0"}}"#,
+        ),
+        ("c", PackageKind::Npm, r#"{"name":"c","version":"1.0.0"}"#),
+      ],
+    );
+    let options = ReleaseOptions {
+      ignore: vec![String::from("a"), String::from("b")],
+      ..ReleaseOptions::default()
+    };
+
+    let changesets = [
+      changeset("one", &[("a", VersionType::Major)]),
+      changeset("two", &[("c", VersionType::Minor)]),
+    ];
+    let plan = assemble_release_plan(&changesets, &packages, &options).unwrap();
+    assert_eq!(plan.changesets.len(), 1);
+    assert_eq!(plan.releases.len(), 1);
+    assert_eq!(plan.get("c").unwrap().new_version, "1.1.0");
+    apply_release_plan(&plan, &packages, root, &options).unwrap();
+    assert!(root.join(".changeset/one.md").exists());
+    assert!(!root.join(".changeset/two.md").exists());
+    assert!(!packages.packages[0].dir.join("CHANGELOG.md").exists());
+
+    let mixed = [changeset(
+      "three",
+      &[("a", VersionType::Major), ("c", VersionType::Patch)],
+    )];
+    assert_eq!(
+      assemble_release_plan(&mixed, &packages, &options)
+        .unwrap_err()
+        .to_string(),
+      "Changeset three releases ignored package \"a\" together with \"c\" which is not ignored"
+    );
+  }
+
+  #[test]
+  fn prepend_to_existing_changelog() {
+    let dir = tempfile::tempdir().unwrap();
+    let package = write_package(
+      dir.path(),
+      "a",
+      PackageKind::Npm,
+      r#"{"name":"a","version":"1.0.0"}"#,
+    );
+    std::fs::write(
+      package.dir.join("CHANGELOG.md"),
+      "# a\n\n## 1.0.0\n\n### Major Changes\n\n- first\n",
+    )
+    .unwrap();
+
+    write_changelog(
+      &package,
+      DEFAULT_CHANGELOG_FILE,
+      "## 1.1.0\n\n### Minor Changes\n\n- second\n",
+    )
+    .unwrap();
+    assert_eq!(
+      std::fs::read_to_string(package.dir.join("CHANGELOG.md")).unwrap(),
+      "# a\n\n## 1.1.0\n\n### Minor Changes\n\n- second\n\n## 1.0.0\n\n### Major Changes\n\n- first\n"
+    );
+  }
+}
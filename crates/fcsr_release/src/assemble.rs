@@ -0,0 +1,309 @@
+use crate::{increment_version, releasable_packages, ReleaseOptions};
+use fcsr_changeset::{Changeset, VersionType};
+use fcsr_config::{
+  dependency_graph::{get_dependency_graph, satisfies, DependencyKind, DependencySpec},
+  PackageGroup,
+};
+use fcsr_pkg::{
+  packages::{Package, PackageKind, Packages},
+  pep440, PkgJson,
+};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use thiserror::Error;
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageRelease {
+  pub name: String,
+  pub r#type: VersionType,
+  pub old_version: String,
+  pub new_version: String,
+  pub changesets: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReleasePlan {
+  pub changesets: Vec<Changeset>,
+  pub releases: Vec<PackageRelease>,
+}
+
+impl ReleasePlan {
+  pub fn get(&self, name: &str) -> Option<&PackageRelease> {
+    self.releases.iter().find(|release| release.name == name)
+  }
+}
+
+#[derive(Debug, Error)]
+pub enum PlanError {
+  #[error("Found changeset {0} for package {1} which is not in the workspace")]
+  UnknownPackage(String, String),
+  #[error("Cannot bump \"{0}\" from invalid version \"{1}\"")]
+  InvalidVersion(String, String),
+  #[error(
+    "Changeset {0} releases ignored package \"{1}\" together with \"{2}\" which is not ignored"
+  )]
+  MixedIgnore(String, String, String),
+}
+
+/// Whether `package_json` depends on `name` outside of its dev dependencies.
+pub(crate) fn depends_on(package_json: &PkgJson, name: &str) -> bool {
+  [
+    DependencyKind::Dependencies,
+    DependencyKind::OptionalDependencies,
+    DependencyKind::PeerDependencies,
+  ]
+  .into_iter()
+  .flat_map(|kind| kind.section(package_json))
+  .flatten()
+  .any(|(alias, range)| DependencySpec::parse(alias, range).name == name)
+}
+
+fn version_key(package: &Package) -> Vec<u64> {
+  let version = &package.package_json.version;
+  let mut key = match package.kind {
+    PackageKind::Python => pep440::Version::parse(version)
+      .map(|version| version.release)
+      .unwrap_or_default(),
+    _ => semver::Version::parse(version)
+      .map(|version| vec![version.major, version.minor, version.patch])
+      .unwrap_or_default(),
+  };
+  key.resize(key.len().max(3), 0);
+  key
+}
+
+struct Planner<'a> {
+  packages_by_name: HashMap<&'a str, &'a Package>,
+  options: &'a ReleaseOptions,
+  bumps: BTreeMap<String, (VersionType, Vec<String>)>,
+}
+
+impl<'a> Planner<'a> {
+  fn r#type(&self, name: &str) -> VersionType {
+    self
+      .bumps
+      .get(name)
+      .map(|(r#type, _)| *r#type)
+      .unwrap_or(VersionType::None)
+  }
+
+  fn versionable(&self, name: &str) -> bool {
+    if self.options.ignore.iter().any(|ignored| ignored == name) {
+      return false;
+    }
+    self.options.private_packages.version
+      || self
+        .packages_by_name
+        .get(name)
+        .is_some_and(|package| package.package_json.private != Some(true))
+  }
+
+  fn bump(&mut self, name: &str, required: VersionType) -> bool {
+    if !self.packages_by_name.contains_key(name) || !self.versionable(name) {
+      return false;
+    }
+    let (current, _) = self
+      .bumps
+      .entry(name.to_string())
+      .or_insert((VersionType::None, vec![]));
+    if *current < required {
+      *current = required;
+      return true;
+    }
+    false
+  }
+
+  fn group_members<'g>(&self, group: &'g PackageGroup) -> Vec<&'g String> {
+    group
+      .iter()
+      .filter(|name| self.packages_by_name.contains_key(name.as_str()))
+      .collect()
+  }
+
+  fn base_package(&self, name: &str) -> &'a Package {
+    let fixed = self
+      .options
+      .fixed
+      .iter()
+      .find(|group| group.iter().any(|member| member == name));
+    let linked = self
+      .options
+      .linked
+      .iter()
+      .find(|group| group.iter().any(|member| member == name));
+
+    let candidates: Vec<&String> = match (fixed, linked) {
+      (Some(group), _) => self.group_members(group),
+      (None, Some(group)) => self
+        .group_members(group)
+        .into_iter()
+        .filter(|member| self.r#type(member) > VersionType::None)
+        .collect(),
+      (None, None) => vec![],
+    };
+
+    candidates
+      .into_iter()
+      .map(|member| self.packages_by_name[member.as_str()])
+      .chain(std::iter::once(self.packages_by_name[name]))
+      .max_by_key(|package| version_key(package))
+      .unwrap()
+  }
+
+  fn new_version(&self, name: &str) -> Result<String, PlanError> {
+    let package = self.packages_by_name[name];
+    let base = self.base_package(name);
+    increment_version(package.kind, &base.package_json.version, self.r#type(name))
+      .ok_or_else(|| PlanError::InvalidVersion(name.to_string(), base.package_json.version.clone()))
+  }
+
+  fn apply_groups(&mut self) -> bool {
+    let mut changed = false;
+    for (groups, fixed) in [(&self.options.fixed, true), (&self.options.linked, false)] {
+      for group in groups.iter() {
+        let members: Vec<String> = self.group_members(group).into_iter().cloned().collect();
+        let highest = members
+          .iter()
+          .map(|member| self.r#type(member))
+          .max()
+          .unwrap_or(VersionType::None);
+        if highest == VersionType::None {
+          continue;
+        }
+        for member in members {
+          if fixed || self.r#type(&member) > VersionType::None {
+            changed |= self.bump(&member, highest);
+          }
+        }
+      }
+    }
+    changed
+  }
+}
+
+pub fn assemble_release_plan(
+  changesets: &[Changeset],
+  packages: &Packages,
+  options: &ReleaseOptions,
+) -> Result<ReleasePlan, PlanError> {
+  let mut planner = Planner {
+    packages_by_name: releasable_packages(packages)
+      .into_iter()
+      .map(|package| (package.package_json.name.as_str(), package))
+      .collect(),
+    options,
+    bumps: BTreeMap::new(),
+  };
+
+  let mut planned = vec![];
+  for changeset in changesets {
+    let is_ignored = |name: &String| options.ignore.contains(name);
+    let ignored = changeset
+      .releases
+      .iter()
+      .find(|release| is_ignored(&release.name));
+    let kept = changeset
+      .releases
+      .iter()
+      .find(|release| !is_ignored(&release.name));
+    match (ignored, kept) {
+      (Some(_), None) => continue,
+      (Some(ignored), Some(kept)) => {
+        return Err(PlanError::MixedIgnore(
+          changeset.id.clone(),
+          ignored.name.clone(),
+          kept.name.clone(),
+        ))
+      }
+      _ => {}
+    }
+    if let Some(release) = changeset
+      .releases
+      .iter()
+      .find(|release| !planner.packages_by_name.contains_key(release.name.as_str()))
+    {
+      return Err(PlanError::UnknownPackage(
+        changeset.id.clone(),
+        release.name.clone(),
+      ));
+    }
+    // Keep changesets whose releases were all dropped, like ones only for private packages, for
+    // a later release.
+    if !changeset.releases.is_empty()
+      && changeset
+        .releases
+        .iter()
+        .all(|release| !planner.versionable(&release.name))
+    {
+      continue;
+    }
+    planned.push(changeset.clone());
+    for release in changeset.releases.iter() {
+      if !planner.versionable(&release.name) {
+        continue;
+      }
+      planner.bump(&release.name, release.r#type);
+      planner
+        .bumps
+        .get_mut(&release.name)
+        .unwrap()
+        .1
+        .push(changeset.id.clone());
+    }
+  }
+
+  let dependency_graph = get_dependency_graph(packages, None);
+  loop {
+    let mut required_bumps = vec![];
+    for name in planner.bumps.keys() {
+      let version = planner.new_version(name)?;
+      for edge in dependency_graph.dependents(name) {
+        let Some(dependent_package) = planner.packages_by_name.get(edge.from.as_str()) else {
+          continue;
+        };
+        let required = match edge.kind {
+          DependencyKind::DevDependencies => continue,
+          DependencyKind::PeerDependencies => VersionType::Major,
+          _ => VersionType::Patch,
+        };
+        let range = packages.resolve_range(name, &edge.range);
+        if satisfies(dependent_package.kind, range, &version) == Some(false) {
+          required_bumps.push((edge.from.clone(), required));
+        }
+      }
+    }
+
+    let mut changed = false;
+    for (dependent, required) in required_bumps {
+      changed |= planner.bump(&dependent, required);
+    }
+    changed |= planner.apply_groups();
+
+    if !changed {
+      break;
+    }
+  }
+
+  let mut releases = vec![];
+  for (name, (r#type, changesets)) in planner.bumps.iter() {
+    if *r#type == VersionType::None {
+      continue;
+    }
+    releases.push(PackageRelease {
+      new_version: planner.new_version(name)?,
+      old_version: planner.packages_by_name[name.as_str()]
+        .package_json
+        .version
+        .clone(),
+      name: name.clone(),
+      r#type: *r#type,
+      changesets: changesets.clone(),
+    });
+  }
+
+  Ok(ReleasePlan {
+    changesets: planned,
+    releases,
+  })
+}
@@ -0,0 +1,54 @@
+mod apply;
+mod assemble;
+mod range;
+
+pub use apply::*;
+pub use assemble::*;
+pub use range::*;
+
+use fcsr_config::{
+  config::PrivatePackages,
+  written::{PrivateChangesets, UpdateInternalDependencies},
+  PackageGroup,
+};
+use fcsr_pkg::packages::{Package, Packages, Tool};
+use std::collections::HashMap;
+
+#[derive(Debug)]
+pub struct ReleaseOptions {
+  pub changelog: bool,
+  /// Per-package changelog paths relative to the package, `None` disabling it.
+  pub changelog_paths: HashMap<String, Option<String>>,
+  pub update_internal_dependencies: UpdateInternalDependencies,
+  pub fixed: Vec<PackageGroup>,
+  pub linked: Vec<PackageGroup>,
+  pub private_packages: PrivatePackages,
+  /// Packages left out of the release along with the changesets touching them.
+  pub ignore: Vec<String>,
+}
+
+impl Default for ReleaseOptions {
+  fn default() -> Self {
+    Self {
+      changelog: true,
+      changelog_paths: HashMap::new(),
+      update_internal_dependencies: UpdateInternalDependencies::Patch,
+      fixed: vec![],
+      linked: vec![],
+      private_packages: PrivatePackages {
+        version: true,
+        tag: false,
+        changesets: PrivateChangesets::Ignore,
+      },
+      ignore: vec![],
+    }
+  }
+}
+
+fn releasable_packages(packages: &Packages) -> Vec<&Package> {
+  let mut releasable: Vec<&Package> = packages.packages.iter().collect();
+  if matches!(packages.tool, Tool::Root) {
+    releasable.push(&packages.root);
+  }
+  releasable
+}
@@ -0,0 +1,205 @@
+use fcsr_changeset::VersionType;
+use fcsr_config::dependency_graph::{DependencySpec, Protocol};
+use fcsr_pkg::{
+  packages::PackageKind,
+  pep440::{self, Operator, Specifier, Specifiers},
+};
+
+fn increment_release(release: &[u64], prerelease: bool, r#type: VersionType) -> Vec<u64> {
+  let segment = |index: usize| release.get(index).copied().unwrap_or(0);
+  let (index, finalize) = match r#type {
+    VersionType::None => return release.to_vec(),
+    VersionType::Major => (0, prerelease && segment(1) == 0 && segment(2) == 0),
+    VersionType::Minor => (1, prerelease && segment(2) == 0),
+    VersionType::Patch => (2, prerelease),
+  };
+
+  let mut incremented: Vec<u64> = (0..release.len().max(index + 1)).map(segment).collect();
+  if !finalize {
+    incremented[index] += 1;
+    incremented[index + 1..]
+      .iter_mut()
+      .for_each(|rest| *rest = 0);
+  }
+  incremented
+}
+
+pub fn increment_version(kind: PackageKind, version: &str, r#type: VersionType) -> Option<String> {
+  if r#type == VersionType::None {
+    return Some(version.to_string());
+  }
+
+  match kind {
+    PackageKind::Npm | PackageKind::Tag => {
+      let current = semver::Version::parse(version).ok()?;
+      let release = increment_release(
+        &[current.major, current.minor, current.patch],
+        !current.pre.is_empty(),
+        r#type,
+      );
+      Some(semver::Version::new(release[0], release[1], release[2]).to_string())
+    }
+    PackageKind::Python => {
+      let current = pep440::Version::parse(version).ok()?;
+      let mut next = current.base();
+      next.release = increment_release(&current.release, current.is_prerelease(), r#type);
+      Some(next.to_string())
+    }
+  }
+}
+
+pub fn update_range(kind: PackageKind, range: &str, version: &str) -> Option<String> {
+  match kind {
+    PackageKind::Npm => {
+      let spec = DependencySpec::parse("", range);
+      if !matches!(spec.protocol, Protocol::Registry | Protocol::Workspace) {
+        return None;
+      }
+      let range = spec.range;
+      let prefix = range
+        .strip_suffix(range.trim_start_matches(['^', '~']))
+        .unwrap_or_default();
+      semver::Version::parse(&range[prefix.len()..]).ok()?;
+      Some(spec.with_range(&format!("{prefix}{version}")))
+    }
+    PackageKind::Python => {
+      let new_version = pep440::Version::parse(version).ok()?;
+      let specifiers = Specifiers::parse(range).ok()?;
+      if specifiers.0.is_empty() {
+        return None;
+      }
+
+      let updated: Vec<Specifier> = specifiers
+        .0
+        .into_iter()
+        .filter_map(|specifier| match specifier.operator {
+          Operator::Compatible => {
+            let segments = pep440::Version::parse(&specifier.version)
+              .ok()?
+              .release
+              .len();
+            let mut compatible = new_version.base();
+            compatible.release.resize(segments, 0);
+            Some(Specifier {
+              version: compatible.to_string(),
+              ..specifier
+            })
+          }
+          Operator::GreaterEqual | Operator::Arbitrary => Some(Specifier {
+            version: version.to_string(),
+            ..specifier
+          }),
+          Operator::Equal if !specifier.version.ends_with(".*") => Some(Specifier {
+            version: version.to_string(),
+            ..specifier
+          }),
+          _ => specifier.contains(&new_version).then_some(specifier),
+        })
+        .collect();
+
+      if updated.is_empty() {
+        return Some(format!(">={version}"));
+      }
+      Some(Specifiers(updated).to_string())
+    }
+    PackageKind::Tag => None,
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn increment_npm_versions() {
+    let cases = [
+      ("1.2.3", VersionType::Major, "2.0.0"),
+      ("1.2.3", VersionType::Minor, "1.3.0"),
+      ("1.2.3", VersionType::Patch, "1.2.4"),
+      ("2.0.0-beta.1", VersionType::Major, "2.0.0"),
+      ("1.2.0-beta.1", VersionType::Minor, "1.2.0"),
+      ("1.2.3-beta.1", VersionType::Minor, "1.3.0"),
+      ("1.2.3", VersionType::None, "1.2.3"),
+    ];
+    for (version, r#type, expected) in cases {
+      assert_eq!(
+        increment_version(PackageKind::Npm, version, r#type).as_deref(),
+        Some(expected)
+      );
+    }
+  }
+
+  #[test]
+  fn increment_python_versions() {
+    let cases = [
+      ("1.2", VersionType::Major, "2.0"),
+      ("1.2", VersionType::Minor, "1.3"),
+      ("1.2", VersionType::Patch, "1.2.1"),
+      ("1.2.3.post1", VersionType::Patch, "1.2.4"),
+      ("1.3.0rc1", VersionType::Minor, "1.3.0"),
+      ("1!0.9", VersionType::Major, "1!1.0"),
+    ];
+    for (version, r#type, expected) in cases {
+      assert_eq!(
+        increment_version(PackageKind::Python, version, r#type).as_deref(),
+        Some(expected)
+      );
+    }
+  }
+
+  #[test]
+  fn update_npm_ranges() {
+    assert_eq!(
+      update_range(PackageKind::Npm, "^1.0.0", "1.1.0").as_deref(),
+      Some("^1.1.0")
+    );
+    assert_eq!(
+      update_range(PackageKind::Npm, "~1.0.0", "2.0.0").as_deref(),
+      Some("~2.0.0")
+    );
+    assert_eq!(
+      update_range(PackageKind::Npm, "1.0.0", "2.0.0").as_deref(),
+      Some("2.0.0")
+    );
+    assert_eq!(
+      update_range(PackageKind::Npm, "workspace:^1.0.0", "2.0.0").as_deref(),
+      Some("workspace:^2.0.0")
+    );
+    assert_eq!(
+      update_range(PackageKind::Npm, "npm:@scope/a@~1.0.0", "1.1.0").as_deref(),
+      Some("npm:@scope/a@~1.1.0")
+    );
+    assert_eq!(
+      update_range(PackageKind::Npm, "workspace:a@1.0.0", "1.1.0").as_deref(),
+      Some("workspace:a@1.1.0")
+    );
+    assert_eq!(update_range(PackageKind::Npm, "workspace:^", "2.0.0"), None);
+    assert_eq!(update_range(PackageKind::Npm, "portal:../a", "2.0.0"), None);
+    assert_eq!(update_range(PackageKind::Npm, ">=1.0.0 <2", "2.0.0"), None);
+  }
+
+  #[test]
+  fn update_python_ranges() {
+    assert_eq!(
+      update_range(PackageKind::Python, ">=1.0,<2", "2.0.0").as_deref(),
+      Some(">=2.0.0")
+    );
+    assert_eq!(
+      update_range(PackageKind::Python, ">=1.0,<3", "2.0.0").as_deref(),
+      Some(">=2.0.0,<3")
+    );
+    assert_eq!(
+      update_range(PackageKind::Python, "~=1.4", "1.5.0").as_deref(),
+      Some("~=1.5")
+    );
+    assert_eq!(
+      update_range(PackageKind::Python, "<1.5", "1.5.0").as_deref(),
+      Some(">=1.5.0")
+    );
+    assert_eq!(update_range(PackageKind::Python, "", "1.5.0"), None);
+    assert_eq!(
+      update_range(PackageKind::Python, "@ file:///x", "1.5.0"),
+      None
+    );
+  }
+}
@@ -0,0 +1,62 @@
+use fcsr_pkg::packages::{Package, PackageKind, Packages, Tool};
+use std::{path::Path, process::Command};
+
+/// Writes `contents` to `root/path`, creating the parent directories.
+pub fn write(root: &Path, path: &str, contents: &str) {
+  let path = root.join(path);
+  std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+  std::fs::write(path, contents).unwrap();
+}
+
+/// Writes the manifest of a `kind` package into `root/dir` and reads it back.
+pub fn write_package(root: &Path, dir: &str, kind: PackageKind, manifest: &str) -> Package {
+  write(
+    root,
+    &format!("{dir}/{}", kind.manifest_file().unwrap()),
+    manifest,
+  );
+  Package::read(root.join(dir), kind).unwrap()
+}
+
+/// A pnpm workspace with a private `root` package and the given `(dir, kind, manifest)` packages.
+pub fn workspace(root: &Path, packages: &[(&str, PackageKind, &str)]) -> Packages {
+  Packages {
+    tool: Tool::Pnpm,
+    catalogs: Default::default(),
+    root: write_package(
+      root,
+      ".",
+      PackageKind::Npm,
+      r#"{"name":"root","version":"0.0.0","private":true}"#,
+    ),
+    packages: packages
+      .iter()
+      .map(|(dir, kind, manifest)| write_package(root, dir, *kind, manifest))
+      .collect(),
+  }
+}
+
+/// Runs git in `cwd`, panicking when it fails.
+pub fn git(cwd: &Path, args: &[&str]) -> String {
+  let output = Command::new("git")
+    .args(["-c", "user.name=fcsr", "-c", "user.email=fcsr@test"])
+    .args(args)
+    .current_dir(cwd)
+    .output()
+    .unwrap();
+  assert!(
+    output.status.success(),
+    "git {}: {}",
+    args.join(" "),
+    String::from_utf8_lossy(&output.stderr)
+  );
+  String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+/// A git repository on the `trunk` branch with one empty commit.
+pub fn init_repo() -> tempfile::TempDir {
+  let dir = tempfile::tempdir().unwrap();
+  git(dir.path(), &["init", "-q", "-b", "trunk"]);
+  git(dir.path(), &["commit", "-q", "--allow-empty", "-m", "init"]);
+  dir
+}
@@ -1,3 +1,34 @@
-use std::path::PathBuf;
+use super::find_up;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
 
-pub fn find_root(cwd: PathBuf) {}
+#[derive(Debug, Error)]
+pub enum FindRootError {
+  #[error("Could not find a project root from {0}, make sure you run fcsr inside a repository that has a package.json or a .changeset directory")]
+  NotFound(PathBuf),
+}
+
+fn is_monorepo_root(dir: &Path) -> bool {
+  if dir.join("pnpm-workspace.yaml").exists() || dir.join("lerna.json").exists() {
+    return true;
+  }
+
+  std::fs::read_to_string(dir.join("package.json"))
+    .ok()
+    .and_then(|source| serde_json::from_str::<serde_json::Value>(&source).ok())
+    .map(|package_json| {
+      package_json.get("workspaces").is_some()
+        || package_json
+          .get("bolt")
+          .and_then(|bolt| bolt.get("workspaces"))
+          .is_some()
+    })
+    .unwrap_or(false)
+}
+
+pub fn find_root(cwd: PathBuf) -> Result<PathBuf, FindRootError> {
+  find_up(&cwd, is_monorepo_root)
+    .or_else(|| find_up(&cwd, |dir| dir.join(".changeset").is_dir()))
+    .or_else(|| find_up(&cwd, |dir| dir.join("package.json").exists()))
+    .ok_or(FindRootError::NotFound(cwd))
+}
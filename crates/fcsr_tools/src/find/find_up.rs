@@ -1 +1,8 @@
+use std::path::{Path, PathBuf};
 
+pub fn find_up(cwd: &Path, predicate: impl Fn(&Path) -> bool) -> Option<PathBuf> {
+  cwd
+    .ancestors()
+    .find(|dir| predicate(dir))
+    .map(Path::to_path_buf)
+}
@@ -1,2 +1,5 @@
 mod find_root;
 mod find_up;
+
+pub use find_root::*;
+pub use find_up::*;
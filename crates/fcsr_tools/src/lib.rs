@@ -1,2 +1,5 @@
 mod find;
 mod packages;
+
+pub use find::*;
+pub use packages::*;
@@ -1 +1,436 @@
-pub fn get_packages() {}
+use fcsr_changeset::{read_pending_versions, ReadError};
+use fcsr_git::{get_tags, GitError};
+use fcsr_pkg::{
+  packages::{Catalogs, Package, PackageKind, Packages, Tool},
+  ManifestError, PkgJson, Workspaces,
+};
+use serde::Deserialize;
+use std::{
+  collections::{HashMap, HashSet},
+  path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+#[derive(Debug, Clone)]
+pub struct TagPackageOptions {
+  pub dir: String,
+  pub name: String,
+  pub tag_prefix: String,
+}
+
+#[derive(Debug, Default)]
+pub struct PackagesOptions {
+  pub python: Vec<String>,
+  pub tag: Vec<TagPackageOptions>,
+}
+
+#[derive(Debug, Error)]
+pub enum PackagesError {
+  #[error(transparent)]
+  Manifest(#[from] ManifestError),
+  #[error("Invalid workspace glob \"{0}\": {1}")]
+  Glob(String, glob::PatternError),
+  #[error("Failed to parse pnpm-workspace.yaml: {0}")]
+  PnpmWorkspace(serde_yaml::Error),
+  #[error("The tag-only package \"{0}\" points at {1}, which is not a directory")]
+  MissingTagPackageDir(String, PathBuf),
+  #[error(transparent)]
+  Git(#[from] GitError),
+  #[error(transparent)]
+  PendingVersions(#[from] ReadError),
+  #[error("The package name \"{0}\" is used by both {1} and {2}")]
+  DuplicateName(String, PathBuf, PathBuf),
+}
+
+#[derive(Deserialize, Default)]
+struct PnpmWorkspace {
+  #[serde(default)]
+  packages: Vec<String>,
+  #[serde(default)]
+  catalog: HashMap<String, String>,
+  #[serde(default)]
+  catalogs: Catalogs,
+}
+
+fn workspace_globs(
+  root: &Path,
+  root_json: &serde_json::Value,
+) -> Result<(Tool, Vec<String>, Catalogs), PackagesError> {
+  let strings = |value: Option<&serde_json::Value>| -> Option<Vec<String>> {
+    value.and_then(|value| value.as_array()).map(|globs| {
+      globs
+        .iter()
+        .filter_map(|glob| glob.as_str().map(String::from))
+        .collect()
+    })
+  };
+
+  if let Ok(source) = std::fs::read_to_string(root.join("pnpm-workspace.yaml")) {
+    let mut workspace = serde_yaml::from_str::<Option<PnpmWorkspace>>(&source)
+      .map_err(PackagesError::PnpmWorkspace)?
+      .unwrap_or_default();
+    if !workspace.catalog.is_empty() {
+      workspace
+        .catalogs
+        .insert(String::from("default"), workspace.catalog);
+    }
+    return Ok((Tool::Pnpm, workspace.packages, workspace.catalogs));
+  }
+  if let Some(workspaces) = root_json
+    .get("workspaces")
+    .and_then(|workspaces| serde_json::from_value::<Workspaces>(workspaces.clone()).ok())
+  {
+    return Ok((Tool::Yarn, workspaces.packages().to_vec(), Catalogs::new()));
+  }
+  if let Some(globs) = strings(
+    root_json
+      .get("bolt")
+      .and_then(|bolt| bolt.get("workspaces")),
+  ) {
+    return Ok((Tool::Bolt, globs, Catalogs::new()));
+  }
+  if let Ok(source) = std::fs::read_to_string(root.join("lerna.json")) {
+    let lerna = serde_json::from_str::<serde_json::Value>(&source).unwrap_or_default();
+    let globs = strings(lerna.get("packages")).unwrap_or_else(|| vec![String::from("packages/*")]);
+    return Ok((Tool::Lerna, globs, Catalogs::new()));
+  }
+  Ok((Tool::Root, vec![], Catalogs::new()))
+}
+
+fn expand_globs(
+  root: &Path,
+  globs: &[String],
+  manifest: &str,
+) -> Result<Vec<PathBuf>, PackagesError> {
+  let mut excluded = vec![];
+  for glob in globs.iter().filter_map(|glob| glob.strip_prefix('!')) {
+    excluded.push(
+      glob::Pattern::new(glob.trim_end_matches('/'))
+        .map_err(|error| PackagesError::Glob(glob.to_string(), error))?,
+    );
+  }
+
+  let mut dirs = vec![];
+  for glob in globs.iter().filter(|glob| !glob.starts_with('!')) {
+    let pattern = root.join(glob.trim_end_matches('/'));
+    let paths = glob::glob(&pattern.to_string_lossy())
+      .map_err(|error| PackagesError::Glob(glob.clone(), error))?;
+    for dir in paths.flatten() {
+      let relative = dir.strip_prefix(root).unwrap_or(&dir);
+      if dir.join(manifest).is_file()
+        && !relative
+          .components()
+          .any(|component| component.as_os_str() == "node_modules")
+        && !excluded
+          .iter()
+          .any(|pattern| pattern.matches_path(relative))
+        && !dirs.contains(&dir)
+      {
+        dirs.push(dir);
+      }
+    }
+  }
+  dirs.sort();
+  Ok(dirs)
+}
+
+fn read_root(root: &Path) -> Result<(Package, serde_json::Value), PackagesError> {
+  let path = root.join("package.json");
+  let root_json = match std::fs::read_to_string(&path) {
+    Ok(source) => serde_json::from_str::<serde_json::Value>(&source)
+      .map_err(|error| ManifestError::Json(path.clone(), error))?,
+    Err(_) => serde_json::json!({}),
+  };
+
+  // The root manifest of a monorepo usually has neither a name nor a version.
+  let mut package_json = root_json.clone();
+  if package_json
+    .get("name")
+    .and_then(|name| name.as_str())
+    .is_none()
+  {
+    package_json["name"] = serde_json::Value::from(
+      root
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default(),
+    );
+  }
+  if package_json
+    .get("version")
+    .and_then(|name| name.as_str())
+    .is_none()
+  {
+    package_json["version"] = serde_json::Value::from("0.0.0");
+  }
+  let package_json: PkgJson =
+    serde_json::from_value(package_json).map_err(|error| ManifestError::Json(path, error))?;
+
+  Ok((
+    Package {
+      package_json,
+      dir: root.to_path_buf(),
+      kind: PackageKind::Npm,
+    },
+    root_json,
+  ))
+}
+
+pub fn latest_tag_version(root: &Path, tag_prefix: &str) -> Result<Option<String>, GitError> {
+  Ok(
+    get_tags(root, tag_prefix)?
+      .iter()
+      .filter_map(|tag| semver::Version::parse(tag.strip_prefix(tag_prefix)?).ok())
+      .max()
+      .map(|version| version.to_string()),
+  )
+}
+
+fn read_tag_packages(
+  root: &Path,
+  options: &[TagPackageOptions],
+) -> Result<Vec<Package>, PackagesError> {
+  if options.is_empty() {
+    return Ok(vec![]);
+  }
+
+  let pending = read_pending_versions(root)?;
+  let mut packages = vec![];
+  for tag_package in options {
+    let dir = root.join(&tag_package.dir);
+    if !dir.is_dir() {
+      return Err(PackagesError::MissingTagPackageDir(
+        tag_package.name.clone(),
+        dir,
+      ));
+    }
+    let version = match pending.get(&tag_package.name) {
+      Some(version) => version.clone(),
+      None => {
+        latest_tag_version(root, &tag_package.tag_prefix)?.unwrap_or_else(|| String::from("0.0.0"))
+      }
+    };
+    packages.push(Package {
+      package_json: PkgJson::new(tag_package.name.clone(), version),
+      dir,
+      kind: PackageKind::Tag,
+    });
+  }
+  Ok(packages)
+}
+
+pub fn get_packages(root: &Path, options: &PackagesOptions) -> Result<Packages, PackagesError> {
+  let (root_package, root_json) = read_root(root)?;
+  let (tool, globs, catalogs) = workspace_globs(root, &root_json)?;
+
+  let mut packages = vec![];
+  for kind in [PackageKind::Npm, PackageKind::Python] {
+    let globs = match kind {
+      PackageKind::Npm => &globs,
+      _ => &options.python,
+    };
+    for dir in expand_globs(root, globs, kind.manifest_file().unwrap())? {
+      packages.push(Package::read(dir, kind)?);
+    }
+  }
+  packages.extend(read_tag_packages(root, &options.tag)?);
+
+  let mut names = HashSet::new();
+  for package in packages.iter() {
+    if !names.insert(package.package_json.name.clone()) {
+      let first = packages
+        .iter()
+        .find(|other| other.package_json.name == package.package_json.name)
+        .unwrap();
+      return Err(PackagesError::DuplicateName(
+        package.package_json.name.clone(),
+        first.dir.clone(),
+        package.dir.clone(),
+      ));
+    }
+  }
+
+  Ok(Packages {
+    tool,
+    packages,
+    root: root_package,
+    catalogs,
+  })
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use fcsr_test_utils::write;
+
+  #[test]
+  fn discovers_npm_and_python_packages() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(
+      root,
+      "package.json",
+      r#"{"private":true,"workspaces":{"packages":["packages/*","!packages/skip"],"nohoist":["**/x"]}}"#,
+    );
+    write(
+      root,
+      "packages/a/package.json",
+      r#"{"name":"a","version":"1.0.0"}"#,
+    );
+    write(
+      root,
+      "packages/skip/package.json",
+      r#"{"name":"skip","version":"1.0.0"}"#,
+    );
+    write(root, "packages/empty/README.md", "");
+    write(
+      root,
+      "clients/py/pyproject.toml",
+      "[project]\nname = \"Py_Client\"\nversion = \"0.3.0\"\ndependencies = [\"py-core>=0.1\"]\n",
+    );
+    write(
+      root,
+      "clients/core/pyproject.toml",
+      "[project]\nname = \"py-core\"\nversion = \"0.1.0\"\n",
+    );
+
+    let packages = get_packages(
+      root,
+      &PackagesOptions {
+        python: vec![String::from("clients/*")],
+        tag: vec![],
+      },
+    )
+    .unwrap();
+
+    assert!(matches!(packages.tool, Tool::Yarn));
+    let names: Vec<_> = packages
+      .packages
+      .iter()
+      .map(|package| (package.package_json.name.as_str(), package.kind))
+      .collect();
+    assert_eq!(
+      names,
+      vec![
+        ("a", PackageKind::Npm),
+        ("py-core", PackageKind::Python),
+        ("py-client", PackageKind::Python)
+      ]
+    );
+    assert_eq!(packages.root.package_json.version, "0.0.0");
+  }
+
+  #[test]
+  fn resolves_tag_package_versions() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(root, "package.json", r#"{"name":"root","version":"1.0.0"}"#);
+    write(root, "charts/api/Chart.yaml", "name: api\n");
+    write(root, "go/sdk/go.mod", "module example.com/go/sdk\n");
+    for args in [
+      vec!["init", "-q"],
+      vec![
+        "-c",
+        "user.name=fcsr",
+        "-c",
+        "user.email=fcsr@test",
+        "commit",
+        "-q",
+        "--allow-empty",
+        "-m",
+        "init",
+      ],
+      vec!["tag", "charts/api/v1.2.0"],
+      vec!["tag", "charts/api/v1.10.0"],
+      vec!["tag", "charts/api/vnext"],
+    ] {
+      assert!(std::process::Command::new("git")
+        .args(args)
+        .current_dir(root)
+        .status()
+        .unwrap()
+        .success());
+    }
+    write(
+      root,
+      ".changeset/pending-versions.json",
+      r#"{"go-sdk":"0.3.0"}"#,
+    );
+
+    let tag_package = |dir: &str, name: &str, tag_prefix: &str| TagPackageOptions {
+      dir: dir.to_string(),
+      name: name.to_string(),
+      tag_prefix: tag_prefix.to_string(),
+    };
+    let packages = get_packages(
+      root,
+      &PackagesOptions {
+        python: vec![],
+        tag: vec![
+          tag_package("charts/api", "api-chart", "charts/api/v"),
+          tag_package("go/sdk", "go-sdk", "go/sdk/v"),
+        ],
+      },
+    )
+    .unwrap();
+
+    let versions: Vec<_> = packages
+      .packages
+      .iter()
+      .map(|package| (package.package_json.version.as_str(), package.kind))
+      .collect();
+    assert_eq!(
+      versions,
+      vec![("1.10.0", PackageKind::Tag), ("0.3.0", PackageKind::Tag)]
+    );
+    assert!(matches!(packages.tool, Tool::Root));
+  }
+
+  #[test]
+  fn reads_pnpm_catalogs() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(
+      root,
+      "pnpm-workspace.yaml",
+      "packages:\n  - 'packages/*'\ncatalog:\n  react: ^18.2.0\ncatalogs:\n  legacy:\n    react: ^16.0.0\n",
+    );
+    write(
+      root,
+      "packages/a/package.json",
+      r#"{"name":"a","version":"1.0.0"}"#,
+    );
+
+    let packages = get_packages(root, &PackagesOptions::default()).unwrap();
+    assert_eq!(packages.resolve_range("react", "catalog:"), "^18.2.0");
+    assert_eq!(
+      packages.resolve_range("react", "catalog:default"),
+      "^18.2.0"
+    );
+    assert_eq!(packages.resolve_range("react", "catalog:legacy"), "^16.0.0");
+    assert_eq!(packages.resolve_range("vue", "catalog:"), "catalog:");
+    assert_eq!(packages.resolve_range("react", "^17.0.0"), "^17.0.0");
+  }
+
+  #[test]
+  fn rejects_duplicate_names() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(root, "pnpm-workspace.yaml", "packages:\n  - 'packages/*'\n");
+    write(
+      root,
+      "packages/a/package.json",
+      r#"{"name":"a","version":"1.0.0"}"#,
+    );
+    write(
+      root,
+      "packages/b/package.json",
+      r#"{"name":"a","version":"1.0.0"}"#,
+    );
+
+    assert!(matches!(
+      get_packages(root, &PackagesOptions::default()),
+      Err(PackagesError::DuplicateName(..))
+    ));
+  }
+}